@@ -15,6 +15,41 @@ use jni::{
 
 static CLASS_MATH: &str = "java/lang/Math";
 static CLASS_OBJECT: &str = "java/lang/Object";
+/// Classes with a no-argument constructor, which all have a `hashCode` method.
+static CLASSES_WITH_DEFAULT_CTOR: &[&str] = &[
+    "java/lang/Object",
+    "java/lang/StringBuilder",
+    "java/lang/StringBuffer",
+    "java/util/ArrayList",
+    "java/util/LinkedList",
+    "java/util/Vector",
+    "java/util/Stack",
+    "java/util/ArrayDeque",
+    "java/util/PriorityQueue",
+    "java/util/HashMap",
+    "java/util/LinkedHashMap",
+    "java/util/TreeMap",
+    "java/util/Hashtable",
+    "java/util/IdentityHashMap",
+    "java/util/WeakHashMap",
+    "java/util/HashSet",
+    "java/util/LinkedHashSet",
+    "java/util/TreeSet",
+    "java/util/BitSet",
+    "java/util/Random",
+    "java/util/Date",
+    "java/util/concurrent/ConcurrentHashMap",
+    "java/util/concurrent/ConcurrentLinkedQueue",
+    "java/util/concurrent/ConcurrentSkipListMap",
+    "java/util/concurrent/CopyOnWriteArrayList",
+    "java/util/concurrent/LinkedBlockingQueue",
+    "java/util/concurrent/atomic/AtomicInteger",
+    "java/util/concurrent/atomic/AtomicLong",
+    "java/util/concurrent/atomic/AtomicBoolean",
+    "java/util/concurrent/atomic/AtomicReference",
+    "java/util/concurrent/locks/ReentrantLock",
+    "java/util/concurrent/locks/ReentrantReadWriteLock",
+];
 static METHOD_MATH_ABS: &str = "abs";
 static METHOD_OBJECT_HASH_CODE: &str = "hashCode";
 static METHOD_CTOR: &str = "<init>";
@@ -130,6 +165,24 @@ mod tests {
         b.iter(|| jni_hash_safe(&env, obj));
     }
 
+    /// Calls `hashCode` on an instance of the last of many classes, all of which have had
+    /// `hashCode` called on them before, to measure how the ID cache of `call_method` scales
+    /// with the number of classes sharing a method name and signature.
+    #[bench]
+    fn jni_call_object_method_safe_many_classes(b: &mut Bencher) {
+        let env = VM.attach_current_thread().unwrap();
+        let objects: Vec<JObject> = CLASSES_WITH_DEFAULT_CTOR
+            .iter()
+            .map(|class| env.new_object(*class, SIG_OBJECT_CTOR, &[]).unwrap())
+            .collect();
+        for obj in &objects {
+            jni_hash_safe(&env, *obj);
+        }
+        let obj = black_box(*objects.last().unwrap());
+
+        b.iter(|| jni_hash_safe(&env, obj));
+    }
+
     #[bench]
     fn jni_call_object_method_unchecked(b: &mut Bencher) {
         let env = VM.attach_current_thread().unwrap();
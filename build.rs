@@ -11,6 +11,9 @@
 //!
 //! On Windows, we also need to find `jvm.lib` file which is used while linking
 //! at build time. This file is typically placed in `$JAVA_HOME/lib` directory.
+//!
+//! With the `dynamic-invocation` feature, nothing is linked: the `jvm` library
+//! is loaded at run time instead.
 
 use std::{
     env,
@@ -26,7 +29,7 @@ const EXPECTED_JVM_FILENAME: &str = "libjvm.so";
 const EXPECTED_JVM_FILENAME: &str = "libjli.dylib";
 
 fn main() {
-    if cfg!(feature = "invocation") {
+    if cfg!(feature = "invocation") && !cfg!(feature = "dynamic-invocation") {
         let java_home = match env::var("JAVA_HOME") {
             Ok(java_home) => PathBuf::from(java_home),
             Err(_) => find_java_home().expect(
@@ -0,0 +1,559 @@
+use std::mem;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    braced,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_macro_input, Attribute, Error, Ident, Result, Token, Visibility,
+};
+
+use crate::sig::{method_signature, Type};
+
+pub fn java_class(input: TokenStream) -> TokenStream {
+    let classes = parse_macro_input!(input as Classes);
+    classes
+        .0
+        .iter()
+        .map(JavaClass::expand)
+        .collect::<TokenStream2>()
+        .into()
+}
+
+/// Any number of class declarations.
+struct Classes(Vec<JavaClass>);
+
+impl Parse for Classes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut classes = Vec::new();
+        while !input.is_empty() {
+            classes.push(input.parse()?);
+        }
+        Ok(Classes(classes))
+    }
+}
+
+/// `<attributes> <visibility> class <name> [as <ident>] { <members> }`
+struct JavaClass {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    /// The binary name of the class, with `/` separators.
+    name: String,
+    ident: Ident,
+    members: Vec<Member>,
+}
+
+impl Parse for JavaClass {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+
+        let keyword = Ident::parse_any(input)?;
+        if keyword != "class" {
+            return Err(Error::new(keyword.span(), "expected `class`"));
+        }
+
+        let span = input.span();
+        // The declared name is taken as written: only member types are resolved.
+        let name = match Type::parse(input, false)? {
+            Type::Object(name) => name,
+            _ => return Err(Error::new(span, "expected a class name")),
+        };
+        let ident = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            input.parse()?
+        } else {
+            let simple_name = name.rsplit(['/', '$']).next().unwrap();
+            Ident::new(simple_name, span)
+        };
+
+        let content;
+        braced!(content in input);
+        let mut members = Vec::new();
+        while !content.is_empty() {
+            let mut member: Member = content.parse()?;
+            member.resolve(&name);
+            members.push(member);
+        }
+
+        Ok(JavaClass {
+            attrs,
+            vis,
+            name,
+            ident,
+            members,
+        })
+    }
+}
+
+impl JavaClass {
+    fn expand(&self) -> TokenStream2 {
+        let JavaClass {
+            attrs,
+            vis,
+            name,
+            ident,
+            members,
+        } = self;
+        let java_name = type_name(&Type::Object(name.clone()));
+        let doc = if attrs.iter().any(|attr| attr.path().is_ident("doc")) {
+            quote!()
+        } else {
+            let doc = format!(" Lifetime'd representation of a `{}`.", java_name);
+            quote!(#[doc = #doc])
+        };
+        let class_name_doc = format!(" The binary name of `{}`.", java_name);
+        let class_doc = format!(" Returns a new local reference to `{}`.", java_name);
+        let members = members.iter().map(|member| member.expand(self));
+
+        quote! {
+            #(#attrs)*
+            #doc
+            #[repr(transparent)]
+            #[derive(Clone, Copy, Debug)]
+            #vis struct #ident<'a>(::jni::objects::JObject<'a>);
+
+            impl<'a> ::std::convert::From<::jni::objects::JObject<'a>> for #ident<'a> {
+                fn from(other: ::jni::objects::JObject<'a>) -> Self {
+                    #ident(other)
+                }
+            }
+
+            impl<'a> ::std::convert::From<#ident<'a>> for ::jni::objects::JObject<'a> {
+                fn from(other: #ident<'a>) -> Self {
+                    other.0
+                }
+            }
+
+            impl<'a> ::jni::NativeReturn for #ident<'a> {
+                type Raw = ::jni::sys::jobject;
+                type Error = ::std::convert::Infallible;
+
+                fn default_raw() -> Self::Raw {
+                    ::std::ptr::null_mut()
+                }
+
+                fn into_raw(self) -> ::std::result::Result<Self::Raw, Self::Error> {
+                    ::std::result::Result::Ok(self.0.into_inner())
+                }
+            }
+
+            // The wrapper is a `repr(transparent)` `JObject`.
+            unsafe impl<'a> ::jni::NativeArg for #ident<'a> {}
+
+            impl<'a> ::std::ops::Deref for #ident<'a> {
+                type Target = ::jni::objects::JObject<'a>;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            /// Looks up the class the wrapper was declared for, not the runtime class of the
+            /// object.
+            impl<'a, 'b> ::jni::descriptors::Desc<'a, ::jni::objects::JClass<'a>> for #ident<'b> {
+                fn lookup(
+                    self,
+                    env: &::jni::JNIEnv<'a>,
+                ) -> ::jni::errors::Result<::jni::objects::JClass<'a>> {
+                    #ident::class(env)
+                }
+            }
+
+            impl<'a> #ident<'a> {
+                #[doc = #class_name_doc]
+                #vis const CLASS_NAME: &'static str = #name;
+
+                fn cached_class() -> &'static ::jni::objects::CachedClass {
+                    static CLASS: ::jni::objects::CachedClass =
+                        ::jni::objects::CachedClass::new(#name);
+                    &CLASS
+                }
+
+                #[doc = #class_doc]
+                #vis fn class(
+                    env: &::jni::JNIEnv<'a>,
+                ) -> ::jni::errors::Result<::jni::objects::JClass<'a>> {
+                    Self::cached_class().get_local(env)
+                }
+
+                #(#members)*
+            }
+        }
+    }
+}
+
+enum Kind {
+    Constructor,
+    Method,
+    Static,
+}
+
+/// `<attributes> [static] fn <name>(<arguments>) [-> <return type>] [as <ident>];` or
+/// `<attributes> new(<arguments>) [as <ident>];`
+struct Member {
+    attrs: Vec<Attribute>,
+    kind: Kind,
+    java_name: String,
+    rust_name: Ident,
+    args: Vec<Type>,
+    ret: Type,
+}
+
+impl Parse for Member {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let kind = if input.peek(Token![static]) {
+            input.parse::<Token![static]>()?;
+            input.parse::<Token![fn]>()?;
+            Kind::Static
+        } else if input.peek(Token![fn]) {
+            input.parse::<Token![fn]>()?;
+            Kind::Method
+        } else {
+            Kind::Constructor
+        };
+
+        let name = Ident::parse_any(input)?.unraw();
+        if let Kind::Constructor = kind {
+            if name != "new" {
+                return Err(Error::new(
+                    name.span(),
+                    "expected `fn`, `static fn` or `new`",
+                ));
+            }
+        }
+
+        let content;
+        parenthesized!(content in input);
+        let args = content.parse_terminated(|input| Type::parse(input, false), Token![,])?;
+        let args = args.into_iter().collect();
+
+        let ret = if input.peek(Token![->]) {
+            let arrow = input.parse::<Token![->]>()?;
+            if let Kind::Constructor = kind {
+                return Err(Error::new(
+                    arrow.spans[0],
+                    "constructors have no return type",
+                ));
+            }
+            Type::parse(input, true)?
+        } else {
+            Type::Primitive("Void", 'V')
+        };
+
+        let rust_name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            input.parse()?
+        } else {
+            match kind {
+                Kind::Constructor => name.clone(),
+                _ => rust_ident(&snake_case(&name.to_string()), &name),
+            }
+        };
+        input.parse::<Token![;]>()?;
+
+        let java_name = match kind {
+            Kind::Constructor => "<init>".to_owned(),
+            _ => name.to_string(),
+        };
+        Ok(Member {
+            attrs,
+            kind,
+            java_name,
+            rust_name,
+            args,
+            ret,
+        })
+    }
+}
+
+impl Member {
+    /// Resolves the unqualified class names of the argument and return types.
+    fn resolve(&mut self, class_name: &str) {
+        self.args = mem::take(&mut self.args)
+            .into_iter()
+            .map(|ty| resolve(ty, class_name))
+            .collect();
+        let ret = mem::replace(&mut self.ret, Type::Primitive("Void", 'V'));
+        self.ret = resolve(ret, class_name);
+    }
+
+    fn expand(&self, class: &JavaClass) -> TokenStream2 {
+        let Member {
+            attrs,
+            kind,
+            java_name,
+            rust_name,
+            args,
+            ret,
+        } = self;
+        let vis = &class.vis;
+        let sig = method_signature(args, ret);
+
+        let doc = if attrs.iter().any(|attr| attr.path().is_ident("doc")) {
+            quote!()
+        } else {
+            let class_name = java_name_of(&class.name);
+            let arg_names: Vec<String> = args.iter().map(type_name).collect();
+            let doc = match kind {
+                Kind::Constructor => format!(
+                    " Calls the constructor `{}({})`.",
+                    class_name,
+                    arg_names.join(", ")
+                ),
+                _ => format!(
+                    " Calls `{}.{}({})`.",
+                    class_name,
+                    java_name,
+                    arg_names.join(", ")
+                ),
+            };
+            quote!(#[doc = #doc])
+        };
+
+        let params: Vec<Ident> = (0..args.len()).map(|i| format_ident!("arg{}", i)).collect();
+        let param_types = args.iter().map(param_type);
+        let values = params.iter().zip(args).map(|(param, ty)| match ty {
+            Type::Primitive(..) => quote!(::jni::objects::JValue::from(#param)),
+            _ => quote!(::jni::objects::JValue::Object(#param.into())),
+        });
+        let params = quote!(#(#params: #param_types),*);
+        let generics = if args.iter().any(|ty| !matches!(ty, Type::Primitive(..))) {
+            quote!(<'b>)
+        } else {
+            quote!()
+        };
+        let values = quote!(&[#(#values),*]);
+
+        let (ret_type, convert) = return_type(ret, class);
+        match kind {
+            Kind::Constructor => quote! {
+                #(#attrs)*
+                #doc
+                #vis fn #rust_name #generics(
+                    env: &::jni::JNIEnv<'a>,
+                    #params
+                ) -> ::jni::errors::Result<Self> {
+                    static METHOD: ::jni::objects::CachedMethodID =
+                        ::jni::objects::CachedMethodID::new(#java_name, #sig);
+                    let class = Self::cached_class();
+                    let method = METHOD.get(env, class)?;
+                    env.new_object_unchecked(class.get(env)?, method, #values)
+                        .map(Self::from)
+                }
+            },
+            Kind::Method => quote! {
+                #(#attrs)*
+                #doc
+                #vis fn #rust_name #generics(
+                    &self,
+                    env: &::jni::JNIEnv<'a>,
+                    #params
+                ) -> ::jni::errors::Result<#ret_type> {
+                    static METHOD: ::jni::objects::CachedMethodID =
+                        ::jni::objects::CachedMethodID::new(#java_name, #sig);
+                    let method = METHOD.get(env, Self::cached_class())?;
+                    env.call_method_unchecked(self.0, method, METHOD.ret(), #values)?
+                        #convert
+                }
+            },
+            Kind::Static => quote! {
+                #(#attrs)*
+                #doc
+                #vis fn #rust_name #generics(
+                    env: &::jni::JNIEnv<'a>,
+                    #params
+                ) -> ::jni::errors::Result<#ret_type> {
+                    static METHOD: ::jni::objects::CachedStaticMethodID =
+                        ::jni::objects::CachedStaticMethodID::new(#java_name, #sig);
+                    let class = Self::cached_class();
+                    let method = METHOD.get(env, class)?;
+                    env.call_static_method_unchecked(class.get(env)?, method, METHOD.ret(), #values)?
+                        #convert
+                }
+            },
+        }
+    }
+}
+
+/// Resolves unqualified class names to the declared class if they are its simple name, and to
+/// `java.lang` otherwise, as Java does.
+fn resolve(ty: Type, class_name: &str) -> Type {
+    match ty {
+        Type::Object(name) if !name.contains('/') => {
+            if class_name.rsplit('/').next() == Some(&*name) {
+                Type::Object(class_name.to_owned())
+            } else {
+                Type::Object(format!("java/lang/{}", name))
+            }
+        }
+        Type::Array(ty) => Type::Array(Box::new(resolve(*ty, class_name))),
+        ty => ty,
+    }
+}
+
+/// The name of a type as written in Java, e.g. `java.lang.String[]`.
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Primitive(variant, _) => variant.to_lowercase(),
+        Type::Object(name) => java_name_of(name),
+        Type::Array(ty) => format!("{}[]", type_name(ty)),
+    }
+}
+
+fn java_name_of(binary_name: &str) -> String {
+    binary_name.replace('/', ".")
+}
+
+/// The Rust type of an argument of the given Java type.
+fn param_type(ty: &Type) -> TokenStream2 {
+    match ty {
+        Type::Primitive("Boolean", _) => quote!(bool),
+        Type::Primitive(variant, _) => {
+            let ty = format_ident!("j{}", variant.to_lowercase());
+            quote!(::jni::sys::#ty)
+        }
+        _ => quote!(impl ::std::convert::Into<::jni::objects::JObject<'b>>),
+    }
+}
+
+/// The Rust type returned for the given Java type, and the conversion from the `JValue`
+/// returned by the call.
+fn return_type(ty: &Type, class: &JavaClass) -> (TokenStream2, TokenStream2) {
+    match ty {
+        Type::Primitive("Void", _) => (quote!(()), quote!(.v())),
+        Type::Primitive("Boolean", _) => (quote!(bool), quote!(.z())),
+        Type::Primitive(variant, descriptor) => {
+            let ty = format_ident!("j{}", variant.to_lowercase());
+            let accessor = format_ident!("{}", descriptor.to_ascii_lowercase());
+            (quote!(::jni::sys::#ty), quote!(.#accessor()))
+        }
+        _ => {
+            let ty = match ty {
+                Type::Object(name) if *name == class.name => {
+                    let ident = &class.ident;
+                    quote!(#ident<'a>)
+                }
+                Type::Object(name) if name == "java/lang/String" => {
+                    quote!(::jni::objects::JString<'a>)
+                }
+                Type::Array(element) => match **element {
+                    Type::Primitive(variant, _) => {
+                        let ty = format_ident!("J{}Array", variant);
+                        quote!(::jni::objects::#ty<'a>)
+                    }
+                    _ => quote!(::jni::objects::JObject<'a>),
+                },
+                _ => quote!(::jni::objects::JObject<'a>),
+            };
+            (ty.clone(), quote!(.l().map(<#ty>::from)))
+        }
+    }
+}
+
+/// Converts a Java method name to snake case, e.g. `getURLFor` to `get_url_for`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_word = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_numeric());
+            let acronym_end = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_word || acronym_end {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Makes an identifier, using a raw identifier for Rust keywords.
+fn rust_ident(name: &str, span: &Ident) -> Ident {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        Ident::new(name, span.span())
+    } else {
+        Ident::new_raw(name, span.span())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("size"), "size");
+        assert_eq!(snake_case("valueOf"), "value_of");
+        assert_eq!(snake_case("getURLFor"), "get_url_for");
+        assert_eq!(snake_case("toURL"), "to_url");
+        assert_eq!(snake_case("get2D"), "get2_d");
+    }
+
+    #[test]
+    fn test_parse_class() {
+        let class: JavaClass = syn::parse_str(
+            "pub class java.util.ArrayList as JArrayList {
+                new();
+                new(int) as with_capacity;
+                fn add(Object) -> boolean;
+                fn add(int, Object) as insert;
+                static fn of(Object[]) -> java.util.List;
+            }",
+        )
+        .unwrap();
+        assert_eq!(class.name, "java/util/ArrayList");
+        assert_eq!(class.ident, "JArrayList");
+
+        let members: Vec<(String, String, String)> = class
+            .members
+            .iter()
+            .map(|member| {
+                let args: String = member.args.iter().map(Type::descriptor).collect();
+                (
+                    member.java_name.clone(),
+                    member.rust_name.to_string(),
+                    format!("({}){}", args, member.ret.descriptor()),
+                )
+            })
+            .collect();
+        let expected = [
+            ("<init>", "new", "()V"),
+            ("<init>", "with_capacity", "(I)V"),
+            ("add", "add", "(Ljava/lang/Object;)Z"),
+            ("add", "insert", "(ILjava/lang/Object;)V"),
+            ("of", "of", "([Ljava/lang/Object;)Ljava/util/List;"),
+        ];
+        for (member, expected) in members.iter().zip(&expected) {
+            assert_eq!(
+                (member.0.as_str(), member.1.as_str(), member.2.as_str()),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_classes() {
+        for input in &[
+            "struct Foo {}",
+            "class int {}",
+            "class Foo { new() -> int; }",
+            "class Foo { fn bar() }",
+            "class Foo { create(); }",
+        ] {
+            assert!(
+                syn::parse_str::<JavaClass>(input).is_err(),
+                "`{}` should be invalid",
+                input
+            );
+        }
+    }
+}
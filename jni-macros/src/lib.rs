@@ -0,0 +1,152 @@
+//! Procedural macros for the [`jni`](https://docs.rs/jni) crate.
+//!
+//! These macros are re-exported by `jni` when its `macros` feature is enabled, and the code
+//! they generate refers to items of `jni`, so they should be used through it:
+//!
+//! ```toml
+//! [dependencies]
+//! jni = { version = "0.17.0", features = ["macros"] }
+//! ```
+
+#![warn(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+mod java_class;
+mod native;
+mod sig;
+
+/// Exports a Rust function as the implementation of a Java `native` method.
+///
+/// The function itself is left untouched. Next to it, the macro generates an
+/// `extern "system"` function exported under the symbol the JVM looks up for the method
+/// (`Java_<class>_<method>`), with the JNI name mangling applied: `_` becomes `_1`, `;`
+/// becomes `_2`, `[` becomes `_3` and any other non-alphanumeric character, like the `$` of
+/// nested classes, becomes its UTF-16 escape (`_00024`).
+///
+/// The first argument of the function is the `JNIEnv`, followed by the class (for static
+/// methods) or the object, and then by the method arguments. Arguments are passed through as
+/// they are, so their types must implement `jni::NativeArg`, which is only implemented for
+/// FFI-compatible types: raw `jni::sys` types or the lifetime-carrying wrappers like `JObject`
+/// and `JString`. Other types, like `bool` or `String`, fail to compile.
+///
+/// The return type must implement `jni::NativeReturn`, like `()`, a primitive (`jint`, `i32`,
+/// `bool`, …), an object (`JObject`, `JString`, any `J*Array`, a `java_class!` wrapper or raw
+/// `jobject`) or a `Result` of one of those. The exported function returns its raw JNI type,
+/// and runs the function through `JNIEnv::throw_on_failure`: errors and panics are thrown as
+/// Java exceptions, in which case a zero or `null` value is returned to Java.
+///
+/// # Attributes
+///
+/// - `class = "com/example/Foo"` (required): the binary name of the class declaring the
+///   method, with either `/` or `.` as separator. Nested classes use `$`, as in `Foo$Bar`.
+/// - `name = "fooBar"`: the Java name of the method, if it differs from the Rust one.
+/// - `sig = "(ILjava/lang/String;)Z"`: the JNI signature of the method. It is required by
+///   the two options below.
+/// - `overloaded`: appends the mangled argument signature to the symbol
+///   (`Java_<class>_<method>__<args>`), as needed for overloaded native methods.
+/// - `native_method`: also generates a `<function>_native_method()` function returning the
+///   `jni::NativeMethod` describing this method, for use with
+///   `JNIEnv::register_native_methods`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jni::{errors::Result, objects::{JClass, JString}, JNIEnv};
+///
+/// #[jni::native(class = "com/example/Foo$Bar", sig = "(Ljava/lang/String;)Ljava/lang/String;")]
+/// fn hello<'a>(env: JNIEnv<'a>, _class: JClass<'a>, input: JString<'a>) -> Result<JString<'a>> {
+///     let input: String = env.get_string(input)?.into();
+///     env.new_string(format!("Hello, {}!", input))
+/// }
+/// // Exported as `Java_com_example_Foo_00024Bar_hello`.
+/// ```
+#[proc_macro_attribute]
+pub fn native(attr: TokenStream, item: TokenStream) -> TokenStream {
+    native::native(attr, item)
+}
+
+/// Builds a JNI signature from a Java-like syntax, checked at compile time.
+///
+/// A method signature is written as `(<argument types>) -> <return type>`, the return type
+/// defaulting to `void`, and a field signature as a single type. Types are either primitives
+/// (`boolean`, `byte`, `char`, `short`, `int`, `long`, `float`, `double` and, for return types,
+/// `void`) or classes written with their fully qualified name, using `$` for nested classes.
+/// Arrays are written with trailing `[]`.
+///
+/// A method signature expands to a `&'static jni::signature::MethodSignature` and a field
+/// signature to a `&'static jni::signature::FieldSignature`. They can be passed to the
+/// checked `JNIEnv` methods (`call_method`, `get_field`, …) in place of a string, and hold the
+/// parsed signature so that nothing gets parsed when calling them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jni::sig;
+///
+/// let sig = sig!((int, java.lang.String) -> boolean);
+/// assert_eq!(sig.as_str(), "(ILjava/lang/String;)Z");
+///
+/// let field = sig!(java.util.Map$Entry[]);
+/// assert_eq!(field.as_str(), "[Ljava/util/Map$Entry;");
+/// ```
+#[proc_macro]
+pub fn sig(input: TokenStream) -> TokenStream {
+    sig::sig(input)
+}
+
+/// Declares typed wrappers for Java classes, with methods calling their Java counterparts.
+///
+/// Each class is declared with its fully qualified name and the members to bind, using the
+/// same type syntax as [`sig!`](macro.sig.html). The declared name is taken as written, so an
+/// unqualified one names a class of the default package. In the member types, the simple name
+/// of the declared class stands for that class, and other unqualified class names are resolved
+/// to `java.lang`, so `String` stands for `java.lang.String`:
+///
+/// - `fn name(<argument types>) -> <return type>;` binds an instance method, the return type
+///   defaulting to `void`;
+/// - `static fn name(<argument types>) -> <return type>;` binds a static method;
+/// - `new(<argument types>);` binds a constructor.
+///
+/// The generated struct is named after the simple name of the class, unless another name is
+/// given with `class <name> as <Ident>`. Its methods are named after the Java ones, converted
+/// to snake case, unless another name is given with a trailing `as <ident>`, as needed for
+/// overloads. Doc comments and other attributes are passed through.
+///
+/// The struct wraps a `JObject` like the other object wrappers of `jni`: it implements
+/// `From<JObject>`, `Into<JObject>` and `Deref<Target = JObject>`, as well as
+/// `Desc<JClass>`, looking up the declared class. The class and the method IDs are looked up
+/// once, the first time they are needed, and cached in statics for all threads, so the calls
+/// skip both the lookups and the signature checks of `JNIEnv::call_method`.
+///
+/// The methods take the `JNIEnv` as their first argument after `self`, and Java primitives as
+/// their `jni::sys` counterparts (`bool` for `boolean`). Objects are taken as any
+/// `Into<JObject>`, and returned as a `JObject`, except for `String` (`JString`), primitive
+/// arrays (`JIntArray`, …) and the declared class itself (the generated struct).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jni::java_class;
+///
+/// java_class! {
+///     pub class java.util.ArrayList as JArrayList {
+///         new();
+///         new(int) as with_capacity;
+///         fn add(Object) -> boolean;
+///         fn add(int, Object) as insert;
+///         fn get(int) -> Object;
+///         fn size() -> int;
+///     }
+/// }
+///
+/// let list = JArrayList::with_capacity(&env, 10)?;
+/// list.add(&env, env.new_string("hello")?)?;
+/// assert_eq!(list.size(&env)?, 1);
+/// ```
+#[proc_macro]
+pub fn java_class(input: TokenStream) -> TokenStream {
+    java_class::java_class(input)
+}
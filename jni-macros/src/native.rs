@@ -0,0 +1,241 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, Error, FnArg, GenericParam, ItemFn, LitStr, ReturnType,
+};
+
+pub fn native(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = NativeArgs::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("class") {
+            args.class = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("name") {
+            args.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("sig") {
+            args.sig = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("overloaded") {
+            args.overloaded = true;
+        } else if meta.path.is_ident("native_method") {
+            args.native_method = true;
+        } else {
+            return Err(meta.error(
+                "unsupported attribute, expected one of `class`, `name`, `sig`, `overloaded` \
+                 or `native_method`",
+            ));
+        }
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+    let func = parse_macro_input!(item as ItemFn);
+
+    expand_native(args, func)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct NativeArgs {
+    class: Option<LitStr>,
+    name: Option<LitStr>,
+    sig: Option<LitStr>,
+    overloaded: bool,
+    native_method: bool,
+}
+
+fn expand_native(args: NativeArgs, func: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &func.sig;
+    let class = args.class.as_ref().ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "missing the class of the native method: `class = \"com/example/Foo\"`",
+        )
+    })?;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new(
+            asyncness.span(),
+            "native methods cannot be async",
+        ));
+    }
+    for param in &sig.generics.params {
+        if !matches!(param, GenericParam::Lifetime(_)) {
+            return Err(Error::new(
+                param.span(),
+                "native methods can only be generic over lifetimes",
+            ));
+        }
+    }
+
+    let mut arg_names = Vec::new();
+    let mut arg_types = Vec::new();
+    for (i, input) in sig.inputs.iter().enumerate() {
+        match input {
+            FnArg::Typed(arg) => {
+                arg_names.push(format_ident!("__arg{}", i));
+                arg_types.push(&arg.ty);
+            }
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new(
+                    receiver.span(),
+                    "native methods cannot take `self`",
+                ));
+            }
+        }
+    }
+    if arg_names.len() < 2 {
+        return Err(Error::new(
+            sig.inputs.span(),
+            "native methods take at least a `JNIEnv` and a `JClass` or `JObject`",
+        ));
+    }
+
+    let java_name = match &args.name {
+        Some(name) => name.value(),
+        None => sig.ident.to_string().trim_start_matches("r#").to_owned(),
+    };
+    let mut symbol = format!(
+        "Java_{}_{}",
+        mangle(&class.value().replace('.', "/")),
+        mangle(&java_name)
+    );
+    if args.overloaded {
+        let method_sig = required_sig(&args, "overloaded")?;
+        symbol.push_str("__");
+        symbol.push_str(&mangle(&arguments_of(method_sig)?));
+    }
+    let symbol = format_ident!("{}", symbol);
+
+    let return_ty = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let raw_ty = quote_spanned!(sig.output.span()=> <#return_ty as ::jni::NativeReturn>::Raw);
+    let default_value = quote!(<#return_ty as ::jni::NativeReturn>::default_raw());
+
+    let fn_name = &sig.ident;
+    let env = &arg_names[0];
+    // Each argument is passed through `native_arg`, which only accepts the types implementing
+    // `NativeArg`, so that those that are not ABI-compatible are rejected.
+    let checked_args = arg_names
+        .iter()
+        .zip(&arg_types)
+        .map(|(name, ty)| quote_spanned!(ty.span()=> ::jni::native_arg::<#ty>(#name)));
+    let body = quote!(::jni::NativeReturn::into_raw(#fn_name(#(#checked_args),*)));
+    let generics = &sig.generics;
+    let where_clause = &sig.generics.where_clause;
+
+    let native_method = if args.native_method {
+        let method_sig = required_sig(&args, "native_method")?;
+        let vis = &func.vis;
+        let descriptor = format_ident!("{}_native_method", fn_name);
+        let doc = format!(
+            "Returns the `NativeMethod` registering `{}` as `{}.{}{}`.",
+            fn_name,
+            class.value(),
+            java_name,
+            method_sig.value()
+        );
+        quote! {
+            #[doc = #doc]
+            #vis fn #descriptor() -> ::jni::NativeMethod {
+                ::jni::NativeMethod {
+                    name: #java_name.into(),
+                    sig: #method_sig.into(),
+                    fn_ptr: #symbol as *mut ::std::os::raw::c_void,
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        #func
+
+        #[doc(hidden)]
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        pub extern "system" fn #symbol #generics(#(#arg_names: #arg_types),*) -> #raw_ty
+        #where_clause
+        {
+            #env.throw_on_failure(#default_value, move |_| #body)
+        }
+
+        #native_method
+    })
+}
+
+fn required_sig<'a>(args: &'a NativeArgs, option: &str) -> syn::Result<&'a LitStr> {
+    args.sig.as_ref().ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            format!(
+                "`{}` requires the JNI signature of the method: `sig = \"(I)V\"`",
+                option
+            ),
+        )
+    })
+}
+
+/// Returns the argument part of a method signature, without the parentheses.
+fn arguments_of(sig: &LitStr) -> syn::Result<String> {
+    let value = sig.value();
+    match (value.find('('), value.find(')')) {
+        (Some(0), Some(end)) => Ok(value[1..end].to_owned()),
+        _ => Err(Error::new(
+            sig.span(),
+            "invalid method signature, expected `(<arguments>)<return type>`",
+        )),
+    }
+}
+
+/// Applies the JNI name mangling to a class name (with `/` separators), a method name or the
+/// arguments of a method signature.
+fn mangle(name: &str) -> String {
+    let mut mangled = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => mangled.push(c),
+            '/' => mangled.push('_'),
+            '_' => mangled.push_str("_1"),
+            ';' => mangled.push_str("_2"),
+            '[' => mangled.push_str("_3"),
+            _ => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    mangled.push_str(&format!("_0{:04x}", unit));
+                }
+            }
+        }
+    }
+    mangled
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mangle_class() {
+        assert_eq!(mangle("HelloWorld"), "HelloWorld");
+        assert_eq!(mangle("com/example/Foo"), "com_example_Foo");
+        assert_eq!(mangle("com/my_app/Foo$Bar"), "com_my_1app_Foo_00024Bar");
+    }
+
+    #[test]
+    fn test_mangle_unicode() {
+        assert_eq!(mangle("caf\u{e9}"), "caf_000e9");
+        assert_eq!(mangle("\u{1F600}"), "_0d83d_0de00");
+    }
+
+    #[test]
+    fn test_mangle_overload_arguments() {
+        let sig = LitStr::new("(I[Ljava/lang/String;J)V", Span::call_site());
+        assert_eq!(
+            mangle(&arguments_of(&sig).unwrap()),
+            "I_3Ljava_lang_String_2J"
+        );
+        assert!(arguments_of(&LitStr::new("I)V", Span::call_site())).is_err());
+    }
+}
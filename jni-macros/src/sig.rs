@@ -0,0 +1,234 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    token::{Bracket, Paren},
+    Error, Ident, Result, Token,
+};
+
+pub fn sig(input: TokenStream) -> TokenStream {
+    let sig = parse_macro_input!(input as Signature);
+    sig.expand().into()
+}
+
+/// A Java type, as written in a `sig!` invocation.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Type {
+    /// The name of the `Primitive` variant and the descriptor of the type.
+    Primitive(&'static str, char),
+    /// The binary name of the class, with `/` separators.
+    Object(String),
+    Array(Box<Type>),
+}
+
+impl Type {
+    pub(crate) fn descriptor(&self) -> String {
+        match self {
+            Type::Primitive(_, descriptor) => descriptor.to_string(),
+            Type::Object(name) => format!("L{};", name),
+            Type::Array(ty) => format!("[{}", ty.descriptor()),
+        }
+    }
+
+    fn expand(&self) -> TokenStream2 {
+        match self {
+            Type::Primitive(variant, _) => {
+                let variant = format_ident!("{}", variant);
+                quote!(::jni::signature::JavaType::Primitive(
+                    ::jni::signature::Primitive::#variant
+                ))
+            }
+            Type::Object(name) => quote!(::jni::signature::JavaType::Object(
+                ::std::string::String::from(#name)
+            )),
+            Type::Array(ty) => {
+                let ty = ty.expand();
+                quote!(::jni::signature::JavaType::Array(::std::boxed::Box::new(#ty)))
+            }
+        }
+    }
+
+    /// Parses a primitive type, `void` if allowed, or a dotted class name, followed by any
+    /// number of `[]`.
+    pub(crate) fn parse(input: ParseStream, allow_void: bool) -> Result<Self> {
+        let ident = Ident::parse_any(input)?.unraw();
+        let mut ty = match ident.to_string().as_str() {
+            "boolean" => Type::Primitive("Boolean", 'Z'),
+            "byte" => Type::Primitive("Byte", 'B'),
+            "char" => Type::Primitive("Char", 'C'),
+            "short" => Type::Primitive("Short", 'S'),
+            "int" => Type::Primitive("Int", 'I'),
+            "long" => Type::Primitive("Long", 'J'),
+            "float" => Type::Primitive("Float", 'F'),
+            "double" => Type::Primitive("Double", 'D'),
+            "void" => Type::Primitive("Void", 'V'),
+            name => {
+                let mut class = name.to_owned();
+                loop {
+                    if input.peek(Token![.]) {
+                        input.parse::<Token![.]>()?;
+                        class.push('/');
+                    } else if input.peek(Token![$]) {
+                        input.parse::<Token![$]>()?;
+                        class.push('$');
+                    } else {
+                        break;
+                    }
+                    class.push_str(&Ident::parse_any(input)?.unraw().to_string());
+                }
+                Type::Object(class)
+            }
+        };
+
+        while input.peek(Bracket) {
+            let content;
+            bracketed!(content in input);
+            if !content.is_empty() {
+                return Err(content.error("expected `[]`"));
+            }
+            ty = Type::Array(Box::new(ty));
+        }
+
+        if let Type::Primitive("Void", _) = ty {
+            if !allow_void {
+                return Err(Error::new(
+                    ident.span(),
+                    "`void` is only allowed as the return type of a method",
+                ));
+            }
+        }
+        if let Type::Array(ref element) = ty {
+            if element.descriptor().ends_with('V') {
+                return Err(Error::new(ident.span(), "arrays of `void` are not allowed"));
+            }
+        }
+        Ok(ty)
+    }
+}
+
+/// A method signature `(<arguments>) -> <return type>` or a field type.
+#[derive(Debug, PartialEq)]
+enum Signature {
+    Method(Vec<Type>, Type),
+    Field(Type),
+}
+
+impl Parse for Signature {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let sig = if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            let args = content.parse_terminated(|input| Type::parse(input, false), Token![,])?;
+            let ret = if input.peek(Token![->]) {
+                input.parse::<Token![->]>()?;
+                Type::parse(input, true)?
+            } else {
+                Type::Primitive("Void", 'V')
+            };
+            Signature::Method(args.into_iter().collect(), ret)
+        } else {
+            Signature::Field(Type::parse(input, false)?)
+        };
+
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the signature"));
+        }
+        Ok(sig)
+    }
+}
+
+impl Signature {
+    fn descriptor(&self) -> String {
+        match self {
+            Signature::Method(args, ret) => {
+                let args: String = args.iter().map(Type::descriptor).collect();
+                format!("({}){}", args, ret.descriptor())
+            }
+            Signature::Field(ty) => ty.descriptor(),
+        }
+    }
+
+    fn expand(&self) -> TokenStream2 {
+        match self {
+            Signature::Method(args, ret) => method_signature(args, ret),
+            Signature::Field(ty) => {
+                let descriptor = self.descriptor();
+                let ty = ty.expand();
+                quote! {{
+                    static SIGNATURE: ::jni::signature::FieldSignature =
+                        ::jni::signature::FieldSignature::__new(#descriptor, || #ty);
+                    &SIGNATURE
+                }}
+            }
+        }
+    }
+}
+
+/// Expands to a `&'static MethodSignature` for a method taking `args` and returning `ret`.
+pub(crate) fn method_signature(args: &[Type], ret: &Type) -> TokenStream2 {
+    let arg_descriptors: String = args.iter().map(Type::descriptor).collect();
+    let descriptor = format!("({}){}", arg_descriptors, ret.descriptor());
+    let args = args.iter().map(Type::expand);
+    let ret = ret.expand();
+    quote! {{
+        static SIGNATURE: ::jni::signature::MethodSignature =
+            ::jni::signature::MethodSignature::__new(#descriptor, || {
+                ::jni::signature::TypeSignature {
+                    args: ::std::vec![#(#args),*],
+                    ret: #ret,
+                }
+            });
+        &SIGNATURE
+    }}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn descriptor(input: &str) -> String {
+        syn::parse_str::<Signature>(input).unwrap().descriptor()
+    }
+
+    #[test]
+    fn test_method_descriptor() {
+        assert_eq!(
+            descriptor("(int, java.lang.String) -> boolean"),
+            "(ILjava/lang/String;)Z"
+        );
+        assert_eq!(descriptor("()"), "()V");
+        assert_eq!(
+            descriptor("(byte[][], char, short, long, float, double) -> java.util.Map$Entry[]"),
+            "([[BCSJFD)[Ljava/util/Map$Entry;"
+        );
+    }
+
+    #[test]
+    fn test_field_descriptor() {
+        assert_eq!(descriptor("long"), "J");
+        assert_eq!(descriptor("java.lang.Object[]"), "[Ljava/lang/Object;");
+    }
+
+    #[test]
+    fn test_invalid_signatures() {
+        for input in &[
+            "(void)",
+            "void",
+            "() -> void[]",
+            "(int[1])",
+            "int int",
+            "(int,,)",
+        ] {
+            assert!(
+                syn::parse_str::<Signature>(input).is_err(),
+                "`{}` should be invalid",
+                input
+            );
+        }
+    }
+}
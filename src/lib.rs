@@ -135,6 +135,18 @@
 //! Note that the type signature for our function is almost identical to the one
 //! from the generated header, aside from our lifetime-carrying arguments.
 //!
+//! With the `macros` feature enabled, the [`native`](attr.native.html) attribute can
+//! generate the exported function instead, taking care of the name mangling and of turning
+//! errors and panics into Java exceptions:
+//!
+//! ```rust,ignore
+//! #[jni::native(class = "HelloWorld")]
+//! fn hello<'a>(env: JNIEnv<'a>, _class: JClass<'a>, input: JString<'a>) -> Result<JString<'a>> {
+//!     let input: String = env.get_string(input)?.into();
+//!     env.new_string(format!("Hello, {}!", input))
+//! }
+//! ```
+//!
 //! ### Final steps
 //!
 //! That's it! Build your crate and try to run your Java class again.
@@ -212,6 +224,9 @@ mod wrapper {
     /// String types for going to/from java strings.
     pub mod strings;
 
+    /// Cache of method and field IDs.
+    mod id_cache;
+
     /// Actual communication with the JVM.
     mod jnienv;
     pub use self::jnienv::*;
@@ -223,6 +238,17 @@ mod wrapper {
     /// Optional thread attachment manager.
     mod executor;
     pub use self::executor::*;
+
+    /// Thread pool running JNI work for asynchronous code.
+    mod async_executor;
+    pub use self::async_executor::*;
+
+    /// Return values of native methods.
+    mod native_return;
+    pub use self::native_return::*;
 }
 
 pub use wrapper::*;
+
+#[cfg(feature = "macros")]
+pub use jni_macros::{java_class, native, sig};
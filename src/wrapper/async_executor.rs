@@ -0,0 +1,460 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{mpsc, Arc, Condvar, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    thread::{self, JoinHandle},
+};
+
+use log::{debug, error};
+
+use crate::{errors::*, objects::JObject, JNIEnv, JavaVM, DEFAULT_LOCAL_FRAME_CAPACITY};
+
+/// The number of tasks that can wait in the queue per thread, by default.
+const DEFAULT_QUEUE_CAPACITY_PER_THREAD: usize = 16;
+
+/// A task, type-erased, as it is run by a worker thread.
+type Job = Box<dyn FnOnce(&JNIEnv) + Send>;
+
+/// Locks `mutex`, ignoring poisoning: the state it guards is only modified by this module,
+/// never while running user code.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+struct Queue {
+    jobs: VecDeque<Job>,
+    capacity: usize,
+    shut_down: bool,
+    /// The futures waiting for room in the queue, each with the waker of its last poll. They
+    /// are identified by the address of their result slot, so that a future polled again does
+    /// not add another waker.
+    blocked: Vec<(usize, Waker)>,
+}
+
+/// The state shared by the executor, its threads and its futures.
+struct Shared {
+    queue: Mutex<Queue>,
+    available: Condvar,
+}
+
+impl Shared {
+    /// Queues the job if there is room for it. Otherwise, returns it back and wakes `waker` once
+    /// there may be room, in place of the waker registered before by the future `id`.
+    fn try_push(&self, job: Job, id: usize, waker: &Waker) -> Result<Option<Job>> {
+        let mut queue = lock(&self.queue);
+        if queue.shut_down {
+            return Err(Error::ExecutorShutdown);
+        }
+        if queue.jobs.len() >= queue.capacity {
+            match queue.blocked.iter_mut().find(|(blocked, _)| *blocked == id) {
+                Some((_, blocked)) => {
+                    if !blocked.will_wake(waker) {
+                        *blocked = waker.clone();
+                    }
+                }
+                None => queue.blocked.push((id, waker.clone())),
+            }
+            return Ok(Some(job));
+        }
+
+        queue.jobs.push_back(job);
+        self.available.notify_one();
+        Ok(None)
+    }
+
+    /// Takes the next job, waiting for one if the queue is empty. Returns `None` once the
+    /// executor is shut down and all the queued jobs have been taken.
+    fn pop(&self) -> Option<Job> {
+        let mut queue = lock(&self.queue);
+        loop {
+            if let Some(job) = queue.jobs.pop_front() {
+                let blocked = mem::take(&mut queue.blocked);
+                drop(queue);
+                blocked.into_iter().for_each(|(_, waker)| waker.wake());
+                return Some(job);
+            }
+            if queue.shut_down {
+                return None;
+            }
+            queue = self
+                .available
+                .wait(queue)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Stops accepting jobs, and lets the threads exit once the queued ones are done.
+    fn shut_down(&self) {
+        let mut queue = lock(&self.queue);
+        queue.shut_down = true;
+        let blocked = mem::take(&mut queue.blocked);
+        drop(queue);
+
+        self.available.notify_all();
+        blocked.into_iter().for_each(|(_, waker)| waker.wake());
+    }
+}
+
+/// Builder for [`AsyncExecutor`](struct.AsyncExecutor.html).
+pub struct AsyncExecutorBuilder {
+    vm: Arc<JavaVM>,
+    threads: usize,
+    queue_capacity: Option<usize>,
+    local_frame_capacity: i32,
+}
+
+impl AsyncExecutorBuilder {
+    /// Creates a builder for an executor using the given VM.
+    pub fn new(vm: Arc<JavaVM>) -> Self {
+        AsyncExecutorBuilder {
+            vm,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            queue_capacity: None,
+            local_frame_capacity: DEFAULT_LOCAL_FRAME_CAPACITY,
+        }
+    }
+
+    /// Set the number of threads of the pool.
+    ///
+    /// Default: the available parallelism, as reported by `std::thread::available_parallelism`.
+    pub fn threads(self, threads: usize) -> Self {
+        assert!(threads > 0, "an executor needs at least one thread");
+        let mut s = self;
+        s.threads = threads;
+        s
+    }
+
+    /// Set the number of tasks that can wait for a thread. Once the queue is full, the futures
+    /// of new tasks stay pending until there is room.
+    ///
+    /// Default: 16 tasks per thread.
+    pub fn queue_capacity(self, capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "the queue capacity should be a positive integer"
+        );
+        let mut s = self;
+        s.queue_capacity = Some(capacity);
+        s
+    }
+
+    /// Set the capacity of the local frame allocated for each task.
+    ///
+    /// Default: [`DEFAULT_LOCAL_FRAME_CAPACITY`](constant.DEFAULT_LOCAL_FRAME_CAPACITY.html)
+    pub fn local_frame_capacity(self, capacity: i32) -> Self {
+        assert!(capacity > 0, "capacity should be a positive integer");
+        let mut s = self;
+        s.local_frame_capacity = capacity;
+        s
+    }
+
+    /// Starts the threads of the executor, and returns once they are all attached to the VM.
+    ///
+    /// If a thread cannot be spawned or attached, the threads already started are stopped and
+    /// the error is returned.
+    pub fn build(self) -> Result<AsyncExecutor> {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                jobs: VecDeque::new(),
+                capacity: self
+                    .queue_capacity
+                    .unwrap_or(self.threads * DEFAULT_QUEUE_CAPACITY_PER_THREAD),
+                shut_down: false,
+                blocked: vec![],
+            }),
+            available: Condvar::new(),
+        });
+
+        let (attached_tx, attached_rx) = mpsc::channel();
+        let mut executor = AsyncExecutor {
+            shared,
+            workers: Vec::with_capacity(self.threads),
+            local_frame_capacity: self.local_frame_capacity,
+        };
+        for i in 0..self.threads {
+            let vm = self.vm.clone();
+            let shared = executor.shared.clone();
+            let attached_tx = attached_tx.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("jni-async-{}", i))
+                .spawn(move || run_worker(&vm, &shared, attached_tx));
+            match spawned {
+                Ok(worker) => executor.workers.push(worker),
+                Err(e) => {
+                    executor.shutdown();
+                    return Err(Error::ExecutorSpawn(e));
+                }
+            }
+        }
+
+        for _ in 0..self.threads {
+            if let Ok(Err(e)) = attached_rx.recv() {
+                executor.shutdown();
+                return Err(e);
+            }
+        }
+        Ok(executor)
+    }
+}
+
+/// Attaches the current thread and runs the jobs of the queue, until the executor is shut down.
+fn run_worker(vm: &JavaVM, shared: &Shared, attached: mpsc::Sender<Result<()>>) {
+    let env = match vm.attach_current_thread_as_daemon() {
+        Ok(env) => env,
+        Err(e) => {
+            let _ = attached.send(Err(e));
+            return;
+        }
+    };
+    let _ = attached.send(Ok(()));
+
+    while let Some(job) = shared.pop() {
+        job(&env);
+        // An exception left by a task must not leak into the next one.
+        if env.exception_check().unwrap_or(false) {
+            debug!("clearing an exception left pending by a task");
+            let _ = env.exception_clear();
+        }
+    }
+
+    vm.detach_current_thread();
+}
+
+/// A pool of threads attached to the JVM, running JNI work for asynchronous code.
+///
+/// Unlike [`Executor`](struct.Executor.html), which runs closures in the calling thread, an
+/// `AsyncExecutor` runs them in a fixed set of threads, permanently attached as daemons, and
+/// returns a `Future` of their result. This keeps the threads of an async runtime from blocking
+/// on Java calls, without attaching and detaching threads for each call. Each task runs in its
+/// own local frame, so that its local references are freed when it returns; an exception it
+/// leaves pending is cleared.
+///
+/// Tasks wait in a bounded queue: once it is full, the futures of new tasks stay pending until
+/// a thread takes a task. Nothing is queued until a future is first polled. A task is still
+/// run if its future is dropped after that, but its result is discarded.
+///
+/// The executor does not depend on a particular async runtime.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// let executor = AsyncExecutorBuilder::new(jvm).threads(4).build()?;
+///
+/// let abs: jint = executor
+///     .spawn(|env| {
+///         env.call_static_method("java/lang/Math", "abs", "(I)I", &[JValue::from(-10)])?
+///             .i()
+///     })
+///     .await?;
+///
+/// executor.shutdown();
+/// ```
+pub struct AsyncExecutor {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+    local_frame_capacity: i32,
+}
+
+impl AsyncExecutor {
+    /// Creates an executor with the given number of threads and the default settings of
+    /// [`AsyncExecutorBuilder`](struct.AsyncExecutorBuilder.html).
+    pub fn new(vm: Arc<JavaVM>, threads: usize) -> Result<Self> {
+        AsyncExecutorBuilder::new(vm).threads(threads).build()
+    }
+
+    /// Returns a future running `f` in one of the threads of the executor, in a new local frame.
+    ///
+    /// The future fails with `Error::ExecutorShutdown` if the executor is shut down before the
+    /// task is queued. If `f` panics, the panic is resumed when the future is polled.
+    pub fn spawn<F, R>(&self, f: F) -> JniFuture<R>
+    where
+        F: FnOnce(&JNIEnv) -> Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(Slot {
+            result: None,
+            waker: None,
+        }));
+        let job_slot = slot.clone();
+        let capacity = self.local_frame_capacity;
+
+        let job: Job = Box::new(move |env: &JNIEnv| {
+            let mut result = None;
+            let frame = env.with_local_frame(capacity, || {
+                result = Some(panic::catch_unwind(AssertUnwindSafe(|| f(env))));
+                Ok(JObject::null())
+            });
+            let result = match (result, frame) {
+                (Some(result), _) => result,
+                (None, Err(e)) => Ok(Err(e)),
+                (None, Ok(_)) => unreachable!("the task runs if the frame is pushed"),
+            };
+
+            let waker = {
+                let mut slot = lock(&job_slot);
+                slot.result = Some(result);
+                slot.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+
+        JniFuture {
+            shared: self.shared.clone(),
+            job: Some(job),
+            slot,
+        }
+    }
+
+    /// Returns the number of threads of the executor.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Shuts the executor down: new tasks are rejected, and this method blocks until the
+    /// queued ones are done and the threads are detached.
+    ///
+    /// When called from one of the tasks of the executor, this does not wait, like `drop`, as
+    /// the thread running the task could not exit until it returns.
+    ///
+    /// Dropping the executor shuts it down too, without waiting for the threads.
+    pub fn shutdown(mut self) {
+        self.shared.shut_down();
+        let current = thread::current().id();
+        if self
+            .workers
+            .iter()
+            .any(|worker| worker.thread().id() == current)
+        {
+            debug!("not waiting for the executor threads, as it is shut down from one of them");
+            return;
+        }
+        for worker in mem::take(&mut self.workers) {
+            if worker.join().is_err() {
+                error!("an executor thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for AsyncExecutor {
+    fn drop(&mut self) {
+        self.shared.shut_down();
+    }
+}
+
+/// The result of a task, set by the thread running it.
+struct Slot<R> {
+    result: Option<thread::Result<Result<R>>>,
+    waker: Option<Waker>,
+}
+
+/// The future of a task run by an [`AsyncExecutor`](struct.AsyncExecutor.html), resolving to
+/// its result.
+#[must_use = "futures do nothing unless polled"]
+pub struct JniFuture<R> {
+    shared: Arc<Shared>,
+    /// The task, until it is queued.
+    job: Option<Job>,
+    slot: Arc<Mutex<Slot<R>>>,
+}
+
+impl<R> Future for JniFuture<R> {
+    type Output = Result<R>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if let Some(job) = this.job.take() {
+            let id = Arc::as_ptr(&this.slot) as *const () as usize;
+            match this.shared.try_push(job, id, cx.waker()) {
+                Ok(None) => {}
+                Ok(Some(job)) => {
+                    this.job = Some(job);
+                    return Poll::Pending;
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+
+        let mut slot = lock(&this.slot);
+        match slot.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(panic)) => {
+                drop(slot);
+                panic::resume_unwind(panic)
+            }
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Condvar, Mutex,
+        },
+        task::{Wake, Waker},
+    };
+
+    use super::{Job, Queue, Shared};
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn job() -> Job {
+        Box::new(|_| {})
+    }
+
+    #[test]
+    fn try_push_keeps_one_waker_per_future() {
+        let shared = Shared {
+            queue: Mutex::new(Queue {
+                jobs: Default::default(),
+                capacity: 1,
+                shut_down: false,
+                blocked: vec![],
+            }),
+            available: Condvar::new(),
+        };
+        let running = Waker::from(Arc::new(CountingWaker(AtomicUsize::new(0))));
+        assert!(shared.try_push(job(), 0, &running).unwrap().is_none());
+
+        let first = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let second = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        for _ in 0..3 {
+            let waker = Waker::from(first.clone());
+            assert!(shared.try_push(job(), 1, &waker).unwrap().is_some());
+        }
+        let waker = Waker::from(second.clone());
+        assert!(shared.try_push(job(), 2, &waker).unwrap().is_some());
+        assert_eq!(shared.queue.lock().unwrap().blocked.len(), 2);
+
+        // A new waker replaces the previous one of the same future.
+        let replacement = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(replacement.clone());
+        assert!(shared.try_push(job(), 1, &waker).unwrap().is_some());
+        assert_eq!(shared.queue.lock().unwrap().blocked.len(), 2);
+
+        shared.shut_down();
+        assert_eq!(first.0.load(Ordering::SeqCst), 0);
+        assert_eq!(replacement.0.load(Ordering::SeqCst), 1);
+        assert_eq!(second.0.load(Ordering::SeqCst), 1);
+    }
+}
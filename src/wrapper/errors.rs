@@ -1,9 +1,11 @@
 #![allow(missing_docs)]
 
+use std::fmt;
+
 use thiserror::Error;
 
-use crate::sys;
 use crate::wrapper::signature::TypeSignature;
+use crate::{sys, JNIVersion};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -21,12 +23,18 @@ pub enum Error {
     FieldNotFound { name: String, sig: String },
     #[error("Java exception was thrown")]
     JavaException,
+    #[error(transparent)]
+    CapturedException(CapturedException),
     #[error("JNIEnv null method pointer for {0}")]
     JNIEnvMethodNotFound(&'static str),
     #[error("Null pointer in {0}")]
     NullPtr(&'static str),
     #[error("Null pointer deref in {0}")]
     NullDeref(&'static str),
+    #[error("Object is not an instance of the given class in {0}")]
+    NotInstanceOf(&'static str),
+    #[error("Reflected member is not static in {0}")]
+    NotStatic(&'static str),
     #[error("Mutex already locked")]
     TryLock,
     #[error("JavaVM null method pointer for {0}")]
@@ -39,8 +47,24 @@ pub enum Error {
     ParseFailed(#[source] combine::error::StringStreamError, String),
     #[error("Internal null in option: {0}")]
     JvmError(String),
+    #[error("{0}")]
+    InvocationFailed(String),
+    #[error("No Java VM has been created in this process")]
+    NoJavaVM,
+    #[error("The Java VM has been destroyed")]
+    JavaVMDestroyed,
+    #[error("The executor has been shut down")]
+    ExecutorShutdown,
+    #[error("Failed to spawn an executor thread")]
+    ExecutorSpawn(#[source] std::io::Error),
     #[error("JNI call failed")]
     JniCall(#[source] JniError),
+    #[error("{function} requires JNI version {required:?}, but the VM only supports {actual:?}")]
+    UnsupportedVersion {
+        function: &'static str,
+        required: JNIVersion,
+        actual: JNIVersion,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -81,6 +105,41 @@ pub fn jni_error_code_to_result(code: sys::jint) -> Result<()> {
     .map_err(Error::JniCall)
 }
 
+/// A Java exception that has been cleared from the JVM and converted to Rust strings, see
+/// [`JNIEnv::take_exception`](struct.JNIEnv.html#method.take_exception).
+///
+/// `source()` returns the cause of the exception (`Throwable.getCause()`), if any, so the
+/// whole Java cause chain can be walked with the standard `Error` API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedException {
+    /// The binary name of the exception class, e.g. `java.lang.IllegalStateException`.
+    pub class: String,
+    /// The result of `Throwable.getMessage()`, which may be `null`.
+    pub message: Option<String>,
+    /// The `StackTraceElement`s of the exception, each converted with `toString()`.
+    pub stack_trace: Vec<String>,
+    /// The cause of the exception, if any.
+    pub cause: Option<Box<CapturedException>>,
+}
+
+impl fmt::Display for CapturedException {
+    /// Formats the exception like `Throwable.toString()` does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(ref message) => write!(f, "{}: {}", self.class, message),
+            None => write!(f, "{}", self.class),
+        }
+    }
+}
+
+impl std::error::Error for CapturedException {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_deref()
+            .map(|cause| cause as &(dyn std::error::Error + 'static))
+    }
+}
+
 pub struct Exception {
     pub class: String,
     pub msg: String,
@@ -89,3 +148,22 @@ pub struct Exception {
 pub trait ToException {
     fn to_exception(&self) -> Exception;
 }
+
+impl ToException for Exception {
+    fn to_exception(&self) -> Exception {
+        Exception {
+            class: self.class.clone(),
+            msg: self.msg.clone(),
+        }
+    }
+}
+
+/// Every `Error` is thrown as a `java.lang.RuntimeException` carrying its description.
+impl ToException for Error {
+    fn to_exception(&self) -> Exception {
+        Exception {
+            class: "java/lang/RuntimeException".to_owned(),
+            msg: self.to_string(),
+        }
+    }
+}
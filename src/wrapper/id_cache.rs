@@ -0,0 +1,343 @@
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JClass, WeakRef},
+    signature::{AsJavaType, AsMethodSignature, JavaType},
+    strings::MemberName,
+    sys::{jfieldID, jint, jmethodID, jvalue},
+    JNIEnv,
+};
+
+/// The number of classes above which the entries of unloaded classes start being removed.
+const MIN_SWEEP_AT: usize = 64;
+
+/// The cached IDs of the members of a class, by name and then by signature, so that they can
+/// be looked up with borrowed strings.
+type Members<T> = HashMap<Box<str>, HashMap<Box<str>, Arc<T>>>;
+
+/// A method ID, along with the parts of its signature the checked calls need.
+pub(crate) struct CachedMethod {
+    pub(crate) id: jmethodID,
+    pub(crate) arg_count: usize,
+    pub(crate) ret: JavaType,
+}
+
+/// A field ID, along with the type of the field.
+pub(crate) struct CachedField {
+    pub(crate) id: jfieldID,
+    pub(crate) ty: JavaType,
+}
+
+// Method and field IDs are valid in any thread, as long as their class is not unloaded.
+unsafe impl Send for CachedMethod {}
+unsafe impl Sync for CachedMethod {}
+unsafe impl Send for CachedField {}
+unsafe impl Sync for CachedField {}
+
+/// The cached IDs of a class. The class is only weakly referenced: once it is unloaded, the
+/// entry no longer matches any class, so its IDs are never used again.
+struct ClassIds {
+    class: WeakRef,
+    methods: Members<CachedMethod>,
+    static_methods: Members<CachedMethod>,
+    fields: Members<CachedField>,
+}
+
+#[derive(Default)]
+struct Classes {
+    /// The entries of the classes, by identity hash code.
+    by_hash: HashMap<jint, Vec<ClassIds>>,
+    len: usize,
+    sweep_at: usize,
+}
+
+/// `java.lang.System` and the ID of its `identityHashCode` method.
+struct IdentityHashCode {
+    system: GlobalRef,
+    method: jmethodID,
+}
+
+unsafe impl Send for IdentityHashCode {}
+unsafe impl Sync for IdentityHashCode {}
+
+/// Process-wide cache of the method and field IDs looked up by the checked `JNIEnv` methods
+/// (`call_method`, `call_nonvirtual_method`, `get_field` and `set_field`) and by the
+/// `CachedMethodID`s of the `java_class!` wrappers, so that only the first call for a given
+/// class, name and signature looks up the ID and parses the signature.
+///
+/// The IDs are grouped by class, found by identity hash code and compared with `IsSameObject`,
+/// so classes with the same name loaded by different class loaders get distinct entries. The
+/// classes are only weakly referenced, so that they can still be unloaded, and the entries of
+/// unloaded classes are removed as new classes are added.
+#[derive(Default)]
+pub(crate) struct IdCache {
+    classes: RwLock<Classes>,
+    identity_hash_code: OnceLock<IdentityHashCode>,
+}
+
+impl IdCache {
+    /// Returns the cache shared by all threads.
+    pub(crate) fn get() -> &'static IdCache {
+        static CACHE: OnceLock<IdCache> = OnceLock::new();
+        CACHE.get_or_init(IdCache::default)
+    }
+
+    /// Looks up the ID of an instance method of `class`.
+    ///
+    /// `check` is given the number of arguments of the method, before its ID is looked up.
+    pub(crate) fn method<'a, S, T, F>(
+        &self,
+        env: &JNIEnv<'a>,
+        class: JClass,
+        name: S,
+        sig: &T,
+        check: F,
+    ) -> Result<Arc<CachedMethod>>
+    where
+        S: MemberName,
+        T: AsMethodSignature,
+        F: FnOnce(usize) -> Result<()>,
+    {
+        self.lookup_method(env, class, name, sig, check, false)
+    }
+
+    /// Looks up the ID of a static method of `class`, like [`IdCache::method`].
+    pub(crate) fn static_method<'a, S, T, F>(
+        &self,
+        env: &JNIEnv<'a>,
+        class: JClass,
+        name: S,
+        sig: &T,
+        check: F,
+    ) -> Result<Arc<CachedMethod>>
+    where
+        S: MemberName,
+        T: AsMethodSignature,
+        F: FnOnce(usize) -> Result<()>,
+    {
+        self.lookup_method(env, class, name, sig, check, true)
+    }
+
+    fn lookup_method<'a, S, T, F>(
+        &self,
+        env: &JNIEnv<'a>,
+        class: JClass,
+        name: S,
+        sig: &T,
+        check: F,
+        is_static: bool,
+    ) -> Result<Arc<CachedMethod>>
+    where
+        S: MemberName,
+        T: AsMethodSignature,
+        F: FnOnce(usize) -> Result<()>,
+    {
+        let hash = self.identity_hash_code(env, class)?;
+        let cached = self.find(env, class, hash, |ids| {
+            let methods = if is_static {
+                &ids.static_methods
+            } else {
+                &ids.methods
+            };
+            get_member(methods, &name.as_name(), sig.signature_str())
+        })?;
+        if let Some(method) = cached {
+            check(method.arg_count)?;
+            return Ok(method);
+        }
+
+        let parsed = sig.as_method_signature()?.into_owned();
+        check(parsed.args.len())?;
+        let key = member_key(&name, sig.signature_str());
+        let id = if is_static {
+            env.get_static_method_id(class, name, sig.signature_str())?
+                .into_inner()
+        } else {
+            env.get_method_id(class, name, sig.signature_str())?
+                .into_inner()
+        };
+        let method = Arc::new(CachedMethod {
+            id,
+            arg_count: parsed.args.len(),
+            ret: parsed.ret,
+        });
+        self.insert(env, class, hash, |ids| {
+            let methods = if is_static {
+                &mut ids.static_methods
+            } else {
+                &mut ids.methods
+            };
+            insert_member(methods, key, method.clone())
+        })?;
+        Ok(method)
+    }
+
+    /// Looks up the ID of an instance field of `class`.
+    ///
+    /// `check` is given the type of the field, before its ID is looked up.
+    pub(crate) fn field<'a, S, T, F>(
+        &self,
+        env: &JNIEnv<'a>,
+        class: JClass,
+        name: S,
+        ty: &T,
+        check: F,
+    ) -> Result<Arc<CachedField>>
+    where
+        S: MemberName,
+        T: AsJavaType,
+        F: FnOnce(&JavaType) -> Result<()>,
+    {
+        let hash = self.identity_hash_code(env, class)?;
+        let cached = self.find(env, class, hash, |ids| {
+            get_member(&ids.fields, &name.as_name(), ty.signature_str())
+        })?;
+        if let Some(field) = cached {
+            check(&field.ty)?;
+            return Ok(field);
+        }
+
+        let parsed = ty.as_java_type()?;
+        check(&parsed)?;
+        let key = member_key(&name, ty.signature_str());
+        let id = env.get_field_id(class, name, ty.signature_str())?;
+        let field = Arc::new(CachedField {
+            id: id.into_inner(),
+            ty: parsed.into_owned(),
+        });
+        self.insert(env, class, hash, |ids| {
+            insert_member(&mut ids.fields, key, field.clone())
+        })?;
+        Ok(field)
+    }
+
+    /// Removes all the entries.
+    pub(crate) fn clear(&self) {
+        // Take the entries out first, so that the weak references are deleted once the lock is
+        // no longer held.
+        let _classes =
+            mem::take(&mut *self.classes.write().unwrap_or_else(PoisonError::into_inner));
+    }
+
+    /// Calls `get` on the entry of `class`, if there is one.
+    fn find<T, F>(&self, env: &JNIEnv, class: JClass, hash: jint, get: F) -> Result<Option<Arc<T>>>
+    where
+        F: FnOnce(&ClassIds) -> Option<Arc<T>>,
+    {
+        let classes = self.classes.read().unwrap_or_else(PoisonError::into_inner);
+        for ids in classes.by_hash.get(&hash).into_iter().flatten() {
+            if ids.class.is_same_object(env, class)? {
+                return Ok(get(ids));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Calls `insert` on the entry of `class`, adding it if needed.
+    fn insert<F>(&self, env: &JNIEnv, class: JClass, hash: jint, insert: F) -> Result<()>
+    where
+        F: FnOnce(&mut ClassIds),
+    {
+        let mut guard = self.classes.write().unwrap_or_else(PoisonError::into_inner);
+        let classes = &mut *guard;
+
+        // Another thread may have added the class since it was looked up.
+        let bucket = classes.by_hash.entry(hash).or_default();
+        for ids in bucket.iter_mut() {
+            if ids.class.is_same_object(env, class)? {
+                insert(ids);
+                return Ok(());
+            }
+        }
+
+        let weak = match env.new_weak_ref(class)? {
+            Some(weak) => weak,
+            None => return Err(Error::NullPtr("id cache class")),
+        };
+        let mut ids = ClassIds {
+            class: weak,
+            methods: HashMap::new(),
+            static_methods: HashMap::new(),
+            fields: HashMap::new(),
+        };
+        insert(&mut ids);
+        bucket.push(ids);
+        classes.len += 1;
+
+        if classes.len >= classes.sweep_at.max(MIN_SWEEP_AT) {
+            let unloaded = classes.sweep(env)?;
+            drop(guard);
+            drop(unloaded);
+        }
+        Ok(())
+    }
+
+    fn identity_hash_code(&self, env: &JNIEnv, class: JClass) -> Result<jint> {
+        let identity_hash_code = match self.identity_hash_code.get() {
+            Some(identity_hash_code) => identity_hash_code,
+            None => {
+                let system = env.auto_local(env.find_class("java/lang/System")?);
+                let method =
+                    env.get_static_method_id(&system, "identityHashCode", "(Ljava/lang/Object;)I")?;
+                let system = env.new_global_ref(&system)?;
+                // Another thread may have set it first, which is fine.
+                let _ = self.identity_hash_code.set(IdentityHashCode {
+                    system,
+                    method: method.into_inner(),
+                });
+                self.identity_hash_code.get().unwrap()
+            }
+        };
+
+        let internal = env.get_native_interface();
+        let args = [jvalue {
+            l: class.into_inner(),
+        }];
+        Ok(jni_non_void_call!(
+            internal,
+            CallStaticIntMethodA,
+            identity_hash_code.system.as_obj().into_inner(),
+            identity_hash_code.method,
+            args.as_ptr()
+        ))
+    }
+}
+
+impl Classes {
+    /// Removes the entries of the classes that have been unloaded, and returns them so that
+    /// their weak references can be deleted once the lock is released.
+    fn sweep(&mut self, env: &JNIEnv) -> Result<Vec<ClassIds>> {
+        let mut unloaded = Vec::new();
+        for bucket in self.by_hash.values_mut() {
+            let mut i = 0;
+            while i < bucket.len() {
+                if bucket[i].class.is_garbage_collected(env)? {
+                    unloaded.push(bucket.swap_remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        self.by_hash.retain(|_, bucket| !bucket.is_empty());
+        self.len -= unloaded.len();
+        self.sweep_at = self.len * 2;
+        Ok(unloaded)
+    }
+}
+
+fn get_member<T>(members: &Members<T>, name: &str, sig: &str) -> Option<Arc<T>> {
+    members.get(name)?.get(sig).cloned()
+}
+
+fn member_key<S: MemberName>(name: &S, sig: &str) -> (Box<str>, Box<str>) {
+    (name.as_name().into(), sig.into())
+}
+
+fn insert_member<T>(members: &mut Members<T>, (name, sig): (Box<str>, Box<str>), value: Arc<T>) {
+    members.entry(name).or_default().insert(sig, value);
+}
@@ -0,0 +1,212 @@
+//! Parsing of the argument files and environment variables accepted by the `java` launcher,
+//! see [`InitArgsBuilder::options_from_file`](struct.InitArgsBuilder.html#method.options_from_file).
+
+use std::{fs, iter::Peekable, path::Path, str::Chars};
+
+use crate::JvmError;
+
+/// The launcher options taking their value as the next argument, and the VM option they are
+/// turned into, followed by the value.
+const OPTIONS_WITH_VALUE: &[(&str, &str)] = &[
+    ("-cp", "-Djava.class.path="),
+    ("-classpath", "-Djava.class.path="),
+    ("--class-path", "-Djava.class.path="),
+    ("-p", "--module-path="),
+    ("--module-path", "--module-path="),
+    ("--upgrade-module-path", "--upgrade-module-path="),
+    ("--add-modules", "--add-modules="),
+    ("--limit-modules", "--limit-modules="),
+    ("--add-reads", "--add-reads="),
+    ("--add-exports", "--add-exports="),
+    ("--add-opens", "--add-opens="),
+    ("--patch-module", "--patch-module="),
+    ("--enable-native-access", "--enable-native-access="),
+];
+
+/// The launcher options selecting the application to run, which cannot be passed to a VM.
+const APPLICATION_OPTIONS: &[&str] = &["-jar", "-m", "--module", "--source"];
+
+/// Reads and parses the argument file at `path`.
+pub(crate) fn read_file(path: &Path) -> Result<Vec<String>, JvmError> {
+    let content = fs::read_to_string(path).map_err(|source| JvmError::ReadArgFile {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(parse(&content))
+}
+
+/// Splits `content` into arguments, following the syntax of the launcher argument files:
+///
+/// * arguments are separated by whitespace;
+/// * an argument starting with `#` starts a comment, which ends with the line;
+/// * single or double quotes enclose whitespace and the other kind of quote. Inside them,
+///   `\n`, `\r`, `\t` and `\f` are escape sequences, and a backslash makes any other character
+///   literal. A quote left open ends with the line;
+/// * a backslash at the end of a line continues the argument on the next one, without its
+///   leading whitespace.
+pub(crate) fn parse(content: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut arg: Option<String> = None;
+    let mut quote = None;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            match c {
+                _ if c == q => quote = None,
+                '\n' | '\r' => {
+                    quote = None;
+                    args.extend(arg.take());
+                }
+                '\\' => match chars.next() {
+                    Some('n') => arg.get_or_insert_with(String::new).push('\n'),
+                    Some('r') => arg.get_or_insert_with(String::new).push('\r'),
+                    Some('t') => arg.get_or_insert_with(String::new).push('\t'),
+                    Some('f') => arg.get_or_insert_with(String::new).push('\x0c'),
+                    Some(end @ '\n') | Some(end @ '\r') => skip_continuation(end, &mut chars),
+                    Some(other) => arg.get_or_insert_with(String::new).push(other),
+                    None => {}
+                },
+                _ => arg.get_or_insert_with(String::new).push(c),
+            }
+            continue;
+        }
+
+        match c {
+            ' ' | '\t' | '\x0c' | '\n' | '\r' => args.extend(arg.take()),
+            '#' if arg.is_none() => while chars.next_if(|&c| c != '\n' && c != '\r').is_some() {},
+            '"' | '\'' => {
+                quote = Some(c);
+                arg.get_or_insert_with(String::new);
+            }
+            '\\' if matches!(chars.peek(), Some('\n') | Some('\r')) => {
+                let end = chars.next().unwrap();
+                skip_continuation(end, &mut chars);
+            }
+            _ => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+
+    args
+}
+
+/// Skips the rest of a line ending started by `end`, and the leading whitespace of the next line.
+fn skip_continuation(end: char, chars: &mut Peekable<Chars>) {
+    if end == '\r' {
+        chars.next_if_eq(&'\n');
+    }
+    while chars.next_if(|&c| c == ' ' || c == '\t').is_some() {}
+}
+
+/// Turns launcher arguments into VM options: `@@` escapes are removed, and the launcher
+/// options taking their value as the next argument, like `-cp`, are turned into the
+/// corresponding VM options.
+///
+/// `@argfiles` are expanded with `read_file` if `expand_argfiles` is set, and are rejected
+/// otherwise, like the arguments selecting the application to run.
+pub(crate) fn vm_options(
+    args: Vec<String>,
+    expand_argfiles: bool,
+) -> Result<Vec<String>, JvmError> {
+    let invalid = |reason: String| JvmError::InvalidOption {
+        option: "argfile",
+        reason,
+    };
+
+    let mut options = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if let Some(escaped) = arg.strip_prefix("@@") {
+            options.push(format!("@{}", escaped));
+        } else if let Some(path) = arg.strip_prefix('@') {
+            if !expand_argfiles {
+                return Err(invalid(format!("nested argument file {:?}", arg)));
+            }
+            options.extend(vm_options(read_file(Path::new(path))?, false)?);
+        } else if let Some(&(_, prefix)) = OPTIONS_WITH_VALUE.iter().find(|(name, _)| *name == arg)
+        {
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("missing value after {}", arg)))?;
+            options.push(format!("{}{}", prefix, value));
+        } else if let Some(class_path) = arg.strip_prefix("--class-path=") {
+            options.push(format!("-Djava.class.path={}", class_path));
+        } else if !arg.starts_with('-') || APPLICATION_OPTIONS.contains(&arg.as_str()) {
+            return Err(invalid(format!(
+                "{:?} does not configure the VM: the application to run and its arguments \
+                 are not supported",
+                arg
+            )));
+        } else {
+            options.push(arg);
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "# A comment\n\
+                       -Xmx1g   -Dempty=\"\"\t-Da=\"b c\" # trailing comment\n\
+                       '-Dquote=\"x\"' \"-Dpath=C:\\\\Program Files\\\\App\"\n\
+                       -Dwindows=C:\\dir\\file.jar -Dhash=a#b\n\
+                       -Desc=\"tab\\there\"\n\
+                       -Dcontinued=first,\\\n      second \"-Dopen=unterminated\n\
+                       --add-opens java.base/java.lang=ALL-UNNAMED\r\n";
+
+        assert_eq!(
+            parse(content),
+            vec![
+                "-Xmx1g",
+                "-Dempty=",
+                "-Da=b c",
+                "-Dquote=\"x\"",
+                "-Dpath=C:\\Program Files\\App",
+                "-Dwindows=C:\\dir\\file.jar",
+                "-Dhash=a#b",
+                "-Desc=tab\there",
+                "-Dcontinued=first,second",
+                "-Dopen=unterminated",
+                "--add-opens",
+                "java.base/java.lang=ALL-UNNAMED",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_quoted_continuation() {
+        assert_eq!(parse("\"-Da=b \\\n   c\""), vec!["-Da=b c"]);
+        assert_eq!(parse("''"), vec![""]);
+        assert!(parse("  # only a comment").is_empty());
+    }
+
+    #[test]
+    fn test_vm_options() {
+        let args = parse("-cp a.jar --add-opens java.base/java.lang=ALL-UNNAMED -Xss1m @@literal");
+        assert_eq!(
+            vm_options(args, false).unwrap(),
+            vec![
+                "-Djava.class.path=a.jar",
+                "--add-opens=java.base/java.lang=ALL-UNNAMED",
+                "-Xss1m",
+                "@literal",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vm_options_invalid() {
+        for content in &["-Xmx1g com.example.Main", "-jar app.jar", "-cp", "@other"] {
+            assert!(matches!(
+                vm_options(parse(content), false),
+                Err(JvmError::InvalidOption { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    os::raw::c_void,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use libloading::Library;
+
+use crate::{
+    sys::{self, jint, jsize},
+    JvmError,
+};
+
+#[cfg(target_os = "windows")]
+const JVM_FILENAME: &str = "jvm.dll";
+#[cfg(target_os = "macos")]
+const JVM_FILENAME: &str = "libjvm.dylib";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const JVM_FILENAME: &str = "libjvm.so";
+
+type CreateJavaVM =
+    unsafe extern "system" fn(*mut *mut sys::JavaVM, *mut *mut c_void, *mut c_void) -> jint;
+type GetCreatedJavaVMs =
+    unsafe extern "system" fn(*mut *mut sys::JavaVM, jsize, *mut jsize) -> jint;
+type GetDefaultJavaVMInitArgs = unsafe extern "system" fn(*mut c_void) -> jint;
+
+static INSTALLED: OnceLock<JvmLibrary> = OnceLock::new();
+
+/// The `jvm` dynamic library, loaded at run time.
+///
+/// With the `dynamic-invocation` feature, the `jvm` library is not linked at build time.
+/// Instead, the Invocation API functions (`JNI_CreateJavaVM`, `JNI_GetCreatedJavaVMs` and
+/// `JNI_GetDefaultJavaVMInitArgs`) are resolved from a `JvmLibrary`, which lets the application
+/// pick the JVM at startup.
+///
+/// The library used by [`JavaVM`](struct.JavaVM.html) is the one [installed](#method.install)
+/// for the process. If none is installed when it is first needed, the library found by
+/// [`locate`](#method.locate) is loaded and installed.
+///
+/// *This API requires "dynamic-invocation" feature to be enabled.*
+pub struct JvmLibrary {
+    path: PathBuf,
+    create_java_vm: CreateJavaVM,
+    get_created_java_vms: GetCreatedJavaVMs,
+    get_default_java_vm_init_args: GetDefaultJavaVMInitArgs,
+    // Keeps the library loaded while the function pointers above are in use.
+    _library: Library,
+}
+
+impl JvmLibrary {
+    /// Loads the `jvm` library at `path` and resolves the Invocation API functions.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization routines, so `path` must point to a genuine
+    /// `jvm` library.
+    pub unsafe fn load<P: AsRef<OsStr>>(path: P) -> Result<Self, JvmError> {
+        let path = PathBuf::from(path.as_ref());
+        let load_error = |error| JvmError::LibraryLoad {
+            path: path.clone(),
+            error,
+        };
+
+        let library = Library::new(&path).map_err(load_error)?;
+        let create_java_vm = *library
+            .get::<CreateJavaVM>(b"JNI_CreateJavaVM\0")
+            .map_err(load_error)?;
+        let get_created_java_vms = *library
+            .get::<GetCreatedJavaVMs>(b"JNI_GetCreatedJavaVMs\0")
+            .map_err(load_error)?;
+        let get_default_java_vm_init_args = *library
+            .get::<GetDefaultJavaVMInitArgs>(b"JNI_GetDefaultJavaVMInitArgs\0")
+            .map_err(load_error)?;
+
+        Ok(JvmLibrary {
+            path,
+            create_java_vm,
+            get_created_java_vms,
+            get_default_java_vm_init_args,
+            _library: library,
+        })
+    }
+
+    /// Returns the path the library was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Makes this library the one used by `JavaVM` in this process.
+    ///
+    /// A library can only be installed once: if one is already installed, this one is returned
+    /// in the error.
+    pub fn install(self) -> Result<&'static JvmLibrary, JvmLibrary> {
+        let mut library = Some(self);
+        let installed = INSTALLED.get_or_init(|| library.take().unwrap());
+        match library {
+            None => Ok(installed),
+            Some(library) => Err(library),
+        }
+    }
+
+    /// Returns the library installed for this process, if any.
+    pub fn installed() -> Option<&'static JvmLibrary> {
+        INSTALLED.get()
+    }
+
+    /// Returns the installed library, first loading and installing the one found by `locate`
+    /// if there is none.
+    pub(crate) fn get_or_locate() -> Result<&'static JvmLibrary, JvmError> {
+        if let Some(library) = INSTALLED.get() {
+            return Ok(library);
+        }
+        let library = unsafe { JvmLibrary::load(JvmLibrary::locate()?)? };
+        // If another thread installed a library in the meantime, use that one.
+        Ok(library
+            .install()
+            .unwrap_or_else(|_| INSTALLED.get().unwrap()))
+    }
+
+    /// Finds the path of the `jvm` library of the default Java installation: the one in
+    /// `JAVA_HOME` if it is set, and otherwise the one of the `java` command (see
+    /// [`find_java_home`](#method.find_java_home)).
+    pub fn locate() -> Result<PathBuf, JvmError> {
+        let java_home = match env::var_os("JAVA_HOME") {
+            Some(java_home) => PathBuf::from(java_home),
+            None => JvmLibrary::find_java_home().ok_or_else(|| {
+                JvmError::LibraryNotFound(
+                    "JAVA_HOME is not set and `java` did not report its home directory".into(),
+                )
+            })?,
+        };
+        JvmLibrary::find_libjvm(&java_home).ok_or_else(|| {
+            JvmError::LibraryNotFound(format!("no {} in {}", JVM_FILENAME, java_home.display()))
+        })
+    }
+
+    /// Finds the home directory of the Java installation of the `java` command, by calling
+    /// `java -XshowSettings:properties -version` and parsing the `java.home` property from its
+    /// output.
+    pub fn find_java_home() -> Option<PathBuf> {
+        let output = Command::new("java")
+            .arg("-XshowSettings:properties")
+            .arg("-version")
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        stdout
+            .lines()
+            .chain(stderr.lines())
+            .filter(|line| line.contains("java.home"))
+            .find_map(|line| line.split_once('='))
+            .map(|(_, path)| PathBuf::from(path.trim()))
+    }
+
+    /// Searches `java_home` recursively for the `jvm` library, and returns its path.
+    pub fn find_libjvm<P: AsRef<Path>>(java_home: P) -> Option<PathBuf> {
+        let mut dirs = vec![java_home.as_ref().to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if entry.file_name() == JVM_FILENAME {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) unsafe fn create_java_vm(
+        &self,
+        pvm: *mut *mut sys::JavaVM,
+        penv: *mut *mut c_void,
+        args: *mut c_void,
+    ) -> jint {
+        (self.create_java_vm)(pvm, penv, args)
+    }
+
+    pub(crate) unsafe fn get_created_java_vms(
+        &self,
+        vm_buf: *mut *mut sys::JavaVM,
+        buf_len: jsize,
+        n_vms: *mut jsize,
+    ) -> jint {
+        (self.get_created_java_vms)(vm_buf, buf_len, n_vms)
+    }
+
+    pub(crate) unsafe fn get_default_java_vm_init_args(&self, args: *mut c_void) -> jint {
+        (self.get_default_java_vm_init_args)(args)
+    }
+
+    /// Calls `JNI_GetCreatedJavaVMs` from the `jvm` library already loaded in the process, e.g.
+    /// by the application that loaded this library as a plugin, which may not be the one found
+    /// by `locate`. Returns `None` if no loaded library exports it.
+    pub(crate) unsafe fn get_created_java_vms_in_process(
+        vm_buf: *mut *mut sys::JavaVM,
+        buf_len: jsize,
+        n_vms: *mut jsize,
+    ) -> Option<jint> {
+        #[cfg(unix)]
+        let library = libloading::os::unix::Library::this();
+        #[cfg(windows)]
+        let library = libloading::os::windows::Library::open_already_loaded(JVM_FILENAME).ok()?;
+
+        let get_created_java_vms = library
+            .get::<GetCreatedJavaVMs>(b"JNI_GetCreatedJavaVMs\0")
+            .ok()?;
+        Some(get_created_java_vms(vm_buf, buf_len, n_vms))
+    }
+}
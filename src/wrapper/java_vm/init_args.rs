@@ -1,13 +1,26 @@
-use std::{ffi::CString, os::raw::c_void};
+use std::{
+    env,
+    ffi::{CStr, CString},
+    fmt,
+    os::raw::{c_char, c_int, c_void},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::{Arc, RwLock},
+};
 
+use log::error;
 use thiserror::Error;
 
+use super::argfile;
 use crate::{
-    errors::Error as JniError,
-    sys::{JavaVMInitArgs, JavaVMOption},
+    errors::{self, Error as JniError},
+    sys::{jint, JavaVMInitArgs, JavaVMOption, JNI_OK},
     JNIVersion,
 };
 
+#[cfg(feature = "dynamic-invocation")]
+use crate::JvmLibrary;
+
 /// Errors that can occur when invoking a [`JavaVM`](super::vm::JavaVM) with the
 /// [Invocation API](https://docs.oracle.com/en/java/javase/12/docs/specs/jni/invocation.html).
 #[derive(Debug, Error)]
@@ -15,14 +28,144 @@ pub enum JvmError {
     /// An internal `0` byte was found when constructing a string.
     #[error("internal null in option: {0}")]
     NullOptString(String),
+    /// An argument file could not be read.
+    #[error("could not read argument file {}", path.display())]
+    ReadArgFile {
+        /// The path of the argument file.
+        path: std::path::PathBuf,
+        /// The error reported while reading it.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A JNI call failed, e.g. with `JniError::WrongVersion` when the VM does not support the
+    /// requested JNI version.
+    #[error("JNI call failed")]
+    JniCall(#[source] errors::JniError),
+    /// An option given to one of the typed `InitArgsBuilder` methods is invalid.
+    #[error("invalid {option} option: {reason}")]
+    InvalidOption {
+        /// The kind of option, e.g. `classpath`.
+        option: &'static str,
+        /// Why the option is invalid.
+        reason: String,
+    },
+    /// The `jvm` library could not be found.
+    #[cfg(feature = "dynamic-invocation")]
+    #[error("could not find the jvm library: {0}")]
+    LibraryNotFound(String),
+    /// The `jvm` library could not be loaded, or lacks one of the Invocation API functions.
+    #[cfg(feature = "dynamic-invocation")]
+    #[error("could not load the jvm library from {}", path.display())]
+    LibraryLoad {
+        /// The path of the library.
+        path: std::path::PathBuf,
+        /// The error reported by the loader.
+        #[source]
+        error: libloading::Error,
+    },
 }
 
 impl From<JvmError> for JniError {
     fn from(e: JvmError) -> Self {
-        JniError::JvmError(format!("{}", e))
+        match e {
+            JvmError::JniCall(e) => JniError::JniCall(e),
+            e @ JvmError::NullOptString(_) => JniError::JvmError(format!("{}", e)),
+            e => JniError::InvocationFailed(format!("{}", e)),
+        }
     }
 }
 
+/// Calls `JNI_GetDefaultJavaVMInitArgs`, from the linked or the
+/// [loaded](struct.JvmLibrary.html) `jvm` library.
+fn get_default_java_vm_init_args(args: &mut JavaVMInitArgs) -> Result<jint, JvmError> {
+    let args = args as *mut JavaVMInitArgs as *mut c_void;
+    #[cfg(not(feature = "dynamic-invocation"))]
+    let res = unsafe { crate::sys::JNI_GetDefaultJavaVMInitArgs(args) };
+    #[cfg(feature = "dynamic-invocation")]
+    let res = unsafe { JvmLibrary::get_or_locate()?.get_default_java_vm_init_args(args) };
+    Ok(res)
+}
+
+type VfprintfHook = dyn Fn(&str) + Send + Sync;
+type ExitHook = dyn Fn(i32) + Send + Sync;
+type AbortHook = dyn Fn() + Send + Sync;
+
+/// The Rust callbacks for the `vfprintf`, `exit` and `abort` hooks of the VM.
+#[derive(Clone, Default)]
+struct Hooks {
+    vfprintf: Option<Arc<VfprintfHook>>,
+    exit: Option<Arc<ExitHook>>,
+    abort: Option<Arc<AbortHook>>,
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("vfprintf", &self.vfprintf.is_some())
+            .field("exit", &self.exit.is_some())
+            .field("abort", &self.abort.is_some())
+            .finish()
+    }
+}
+
+// The hooks receive no user data from the VM, so the callbacks of the VM being launched are
+// kept in statics, where the trampolines find them.
+static VFPRINTF_HOOK: RwLock<Option<Arc<VfprintfHook>>> = RwLock::new(None);
+static EXIT_HOOK: RwLock<Option<Arc<ExitHook>>> = RwLock::new(None);
+static ABORT_HOOK: RwLock<Option<Arc<AbortHook>>> = RwLock::new(None);
+
+/// The size of the buffer the messages of the `vfprintf` hook are formatted in. Longer messages
+/// are truncated.
+const VFPRINTF_BUFFER_LEN: usize = 4096;
+
+// With the Universal CRT, `vsnprintf` is only exported by this compatibility library.
+#[cfg_attr(target_env = "msvc", link(name = "legacy_stdio_definitions"))]
+extern "C" {
+    // The `va_list` the VM passes is forwarded as is, which is only sound where it is passed as
+    // a pointer: on x86_64 outside of Windows, where it is an array, and on aarch64 Linux, where
+    // it is a structure larger than 16 bytes, passed by reference. `vfprintf_hook` is only
+    // available on those targets.
+    fn vsnprintf(s: *mut c_char, n: usize, format: *const c_char, args: *mut c_void) -> c_int;
+}
+
+/// Calls the callback installed in `hook`, if any, making sure that a panic does not unwind
+/// into the VM.
+fn call_hook<F: ?Sized>(hook: &RwLock<Option<Arc<F>>>, call: impl FnOnce(&F)) {
+    let hook = match hook.read() {
+        Ok(hook) => hook.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    if let Some(hook) = hook {
+        if panic::catch_unwind(AssertUnwindSafe(|| call(&hook))).is_err() {
+            error!("A JVM hook panicked");
+        }
+    }
+}
+
+unsafe extern "system" fn vfprintf_trampoline(
+    _stream: *mut c_void,
+    format: *const c_char,
+    args: *mut c_void,
+) -> jint {
+    let mut buffer = [0 as c_char; VFPRINTF_BUFFER_LEN];
+    let len = vsnprintf(buffer.as_mut_ptr(), buffer.len(), format, args);
+    if len < 0 {
+        return len;
+    }
+
+    let message = CStr::from_ptr(buffer.as_ptr()).to_string_lossy();
+    call_hook(&VFPRINTF_HOOK, |hook| hook(&message));
+    len
+}
+
+unsafe extern "system" fn exit_trampoline(code: jint) {
+    call_hook(&EXIT_HOOK, |hook| hook(code));
+}
+
+unsafe extern "system" fn abort_trampoline() {
+    call_hook(&ABORT_HOOK, |hook| hook());
+}
+
 /// Builder for JavaVM InitArgs.
 ///
 /// *This API requires "invocation" feature to be enabled,
@@ -32,6 +175,8 @@ pub struct InitArgsBuilder {
     opts: Vec<String>,
     ignore_unrecognized: bool,
     version: JNIVersion,
+    hooks: Hooks,
+    error: Option<JvmError>,
 }
 
 impl Default for InitArgsBuilder {
@@ -40,6 +185,8 @@ impl Default for InitArgsBuilder {
             opts: vec![],
             ignore_unrecognized: false,
             version: JNIVersion::V8,
+            hooks: Hooks::default(),
+            error: None,
         }
     }
 }
@@ -52,7 +199,9 @@ impl InitArgsBuilder {
 
     /// Add an option to the init args
     ///
-    /// The `vfprintf`, `abort`, and `exit` options are unsupported at this time.
+    /// The `vfprintf`, `abort`, and `exit` options are ignored: use
+    /// [`vfprintf_hook`](#method.vfprintf_hook), [`abort_hook`](#method.abort_hook) and
+    /// [`exit_hook`](#method.exit_hook) instead.
     pub fn option(self, opt_string: &str) -> Self {
         let mut s = self;
 
@@ -68,6 +217,9 @@ impl InitArgsBuilder {
 
     /// Set JNI version for the init args
     ///
+    /// Versions newer than V8 (V9, V10, V19, V20 and V21) are only accepted by VMs of the
+    /// corresponding Java release or later.
+    ///
     /// Default: V8
     pub fn version(self, version: JNIVersion) -> Self {
         let mut s = self;
@@ -88,12 +240,256 @@ impl InitArgsBuilder {
         s
     }
 
+    /// Set the class path (`-Djava.class.path`), joining the paths with the separator of the
+    /// platform. This replaces the class path set by a previous call.
+    ///
+    /// `build` fails if a path is not valid UTF-8 or contains the separator.
+    pub fn classpath<I, P>(self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        const PREFIX: &str = "-Djava.class.path=";
+
+        let mut s = self;
+        let classpath = env::join_paths(paths.into_iter().map(|p| p.as_ref().to_path_buf()))
+            .map_err(|e| e.to_string())
+            .and_then(|classpath| {
+                classpath
+                    .into_string()
+                    .map_err(|classpath| format!("{:?} is not valid UTF-8", classpath))
+            });
+        s.opts.retain(|opt| !opt.starts_with(PREFIX));
+        s.checked_option("classpath", classpath.map(|cp| format!("{}{}", PREFIX, cp)))
+    }
+
+    /// Set a system property (`-Dkey=value`).
+    ///
+    /// `build` fails if the key is empty or contains `=`.
+    pub fn system_property(self, key: &str, value: &str) -> Self {
+        let option = if key.is_empty() {
+            Err("the key is empty".to_owned())
+        } else if key.contains('=') {
+            Err(format!("the key {:?} contains '='", key))
+        } else {
+            Ok(format!("-D{}={}", key, value))
+        };
+        self.checked_option("system property", option)
+    }
+
+    /// Set the maximum size of the heap in bytes (`-Xmx`).
+    ///
+    /// `build` fails if the size is below 2 MiB, which HotSpot refuses to start with.
+    pub fn max_heap(self, bytes: u64) -> Self {
+        const MIN: u64 = 2 << 20;
+        const UNITS: [(u64, &str); 3] = [(1 << 30, "g"), (1 << 20, "m"), (1 << 10, "k")];
+
+        let option = if bytes < MIN {
+            Err(format!(
+                "{} bytes is below the minimum of {} bytes",
+                bytes, MIN
+            ))
+        } else {
+            let (size, unit) = UNITS
+                .iter()
+                .find(|(unit, _)| bytes.is_multiple_of(*unit))
+                .map_or((bytes, ""), |(unit, suffix)| (bytes / unit, suffix));
+            Ok(format!("-Xmx{}{}", size, unit))
+        };
+        self.checked_option("max heap", option)
+    }
+
+    /// Open `package` of `module` to the `target` module, or to all unnamed modules if
+    /// `target` is `ALL-UNNAMED` (`--add-opens module/package=target`), for deep reflection.
+    ///
+    /// `build` fails if a name is empty, or contains whitespace, `/`, `=` or `,`.
+    pub fn add_opens(self, module: &str, package: &str, target: &str) -> Self {
+        let invalid = [("module", module), ("package", package), ("target", target)]
+            .iter()
+            .find(|(_, name)| {
+                name.is_empty() || name.contains(|c: char| c.is_whitespace() || "/=,".contains(c))
+            })
+            .map(|(kind, name)| format!("invalid {} name {:?}", kind, name));
+        let option = match invalid {
+            Some(reason) => Err(reason),
+            None => Ok(format!("--add-opens={}/{}={}", module, package, target)),
+        };
+        self.checked_option("add-opens", option)
+    }
+
+    /// Load the native agent library at `path` (`-agentpath:path[=options]`).
+    ///
+    /// `build` fails if the file does not exist or its path is not valid UTF-8.
+    pub fn agent<P: AsRef<Path>>(self, path: P, options: Option<&str>) -> Self {
+        let path = path.as_ref();
+        let option = match path.to_str() {
+            None => Err(format!("{:?} is not valid UTF-8", path)),
+            Some(_) if !path.is_file() => Err(format!("{} does not exist", path.display())),
+            Some(path) => Ok(match options {
+                Some(options) => format!("-agentpath:{}={}", path, options),
+                None => format!("-agentpath:{}", path),
+            }),
+        };
+        self.checked_option("agent", option)
+    }
+
+    /// Add the options of the argument file at `path`, written in the syntax accepted by
+    /// `java @path`:
+    ///
+    /// * options are separated by whitespace, and an option starting with `#` starts a comment
+    ///   running to the end of the line;
+    /// * an option can be enclosed in single or double quotes, in which `\n`, `\r`, `\t` and
+    ///   `\f` are escape sequences and a backslash makes the next character literal;
+    /// * a backslash at the end of a line continues the option on the next line, without its
+    ///   leading whitespace;
+    /// * a leading `@@` is an escaped `@`.
+    ///
+    /// The launcher options taking their value as a separate argument, such as `-cp`,
+    /// `--module-path` or `--add-opens`, are turned into the corresponding VM options.
+    ///
+    /// `build` fails if the file cannot be read, or if it selects an application to run, such
+    /// as with a main class or `-jar`, or refers to another argument file.
+    pub fn options_from_file<P: AsRef<Path>>(self, path: P) -> Self {
+        let options =
+            argfile::read_file(path.as_ref()).and_then(|args| argfile::vm_options(args, false));
+        self.extend_options(options)
+    }
+
+    /// Add the options of the environment variable `name`, such as `JDK_JAVA_OPTIONS`, which
+    /// are parsed like [argument files](#method.options_from_file). Like with the launcher, the
+    /// `@argfiles` it mentions are expanded.
+    ///
+    /// Nothing is added if the variable is not set. `build` fails if its value is not valid
+    /// UTF-8, or for the same reasons as with `options_from_file`.
+    pub fn options_from_env(self, name: &str) -> Self {
+        let options = match env::var(name) {
+            Ok(value) => argfile::vm_options(argfile::parse(&value), true),
+            Err(env::VarError::NotPresent) => return self,
+            Err(env::VarError::NotUnicode(value)) => Err(JvmError::InvalidOption {
+                option: "environment",
+                reason: format!("the value of {} is not valid UTF-8: {:?}", name, value),
+            }),
+        };
+        self.extend_options(options)
+    }
+
+    /// Adds the option if it is valid, or records the error returned by `build` otherwise.
+    fn checked_option(self, option: &'static str, opt_string: Result<String, String>) -> Self {
+        self.extend_options(
+            opt_string
+                .map(|opt_string| vec![opt_string])
+                .map_err(|reason| JvmError::InvalidOption { option, reason }),
+        )
+    }
+
+    /// Adds the options, or records the error returned by `build`.
+    fn extend_options(self, options: Result<Vec<String>, JvmError>) -> Self {
+        let mut s = self;
+        match options {
+            Ok(options) => s.opts.extend(options),
+            Err(error) => {
+                s.error.get_or_insert(error);
+            }
+        }
+        s
+    }
+
+    /// Set the `vfprintf` hook, which receives the messages the VM prints, such as the output
+    /// of `-verbose` or `-Xlog`, instead of the standard output and error streams.
+    ///
+    /// The messages are formatted by the C library and truncated to 4095 bytes.
+    ///
+    /// Like the other hooks, it applies to the VM launched with these `InitArgs`, and replaces
+    /// the hook of any VM launched before in the process. A panic in the hook is caught and
+    /// logged.
+    ///
+    /// The `va_list` of the messages is handed to the C library as a pointer, which has only
+    /// been verified to match its ABI on x86_64 outside of Windows and on aarch64 Linux, so this
+    /// is only available on those targets.
+    #[cfg(any(
+        all(target_arch = "x86_64", not(target_os = "windows")),
+        all(target_arch = "aarch64", target_os = "linux")
+    ))]
+    pub fn vfprintf_hook<F>(self, hook: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        let mut s = self;
+        s.hooks.vfprintf = Some(Arc::new(hook));
+        s
+    }
+
+    /// Set the `exit` hook, which is called with the exit status when the VM exits, e.g. on
+    /// `System.exit`.
+    ///
+    /// The hook cannot prevent the exit: as specified by the Invocation API, the VM exits the
+    /// process with the same status once the hook returns. The hook can only clean up, or exit
+    /// the process itself.
+    pub fn exit_hook<F>(self, hook: F) -> Self
+    where
+        F: Fn(i32) + Send + Sync + 'static,
+    {
+        let mut s = self;
+        s.hooks.exit = Some(Arc::new(hook));
+        s
+    }
+
+    /// Set the `abort` hook, which is called when the VM aborts, e.g. on a fatal error.
+    ///
+    /// The VM aborts the process when the hook returns.
+    pub fn abort_hook<F>(self, hook: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let mut s = self;
+        s.hooks.abort = Some(Arc::new(hook));
+        s
+    }
+
     /// Build the `InitArgs`
     ///
     /// This will check for internal nulls in the option strings and will return
-    /// an error if one is found.
+    /// an error if one is found, as well as the first error of the typed option methods.
+    ///
+    /// It fails with `JniError::WrongVersion` for JNI 1.1, whose init args have a different
+    /// layout. Whether the VM supports other versions is only checked by
+    /// [`JavaVM::new`](struct.JavaVM.html#method.new), so that building the args does not load
+    /// the `jvm` library with the `dynamic-invocation` feature.
     pub fn build(self) -> Result<InitArgs, JvmError> {
-        let mut opts = Vec::with_capacity(self.opts.len());
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.version == JNIVersion::V1 {
+            return Err(JvmError::JniCall(errors::JniError::WrongVersion));
+        }
+
+        let mut opts = Vec::with_capacity(self.opts.len() + 3);
+
+        // The hooks come first, so that they apply to the processing of the other options.
+        let hooks = [
+            (
+                "vfprintf",
+                self.hooks.vfprintf.is_some(),
+                vfprintf_trampoline as *mut c_void,
+            ),
+            (
+                "exit",
+                self.hooks.exit.is_some(),
+                exit_trampoline as *mut c_void,
+            ),
+            (
+                "abort",
+                self.hooks.abort.is_some(),
+                abort_trampoline as *mut c_void,
+            ),
+        ];
+        for &(name, _, trampoline) in hooks.iter().filter(|(_, set, _)| *set) {
+            opts.push(JavaVMOption {
+                optionString: CString::new(name).unwrap().into_raw(),
+                extraInfo: trampoline,
+            });
+        }
+
         for opt in self.opts {
             let option_string =
                 CString::new(opt.as_str()).map_err(|_| JvmError::NullOptString(opt))?;
@@ -112,6 +508,7 @@ impl InitArgsBuilder {
                 nOptions: opts.len() as _,
             },
             opts,
+            hooks: self.hooks,
         })
     }
 
@@ -128,12 +525,61 @@ impl InitArgsBuilder {
 pub struct InitArgs {
     inner: JavaVMInitArgs,
     opts: Vec<JavaVMOption>,
+    hooks: Hooks,
 }
 
 impl InitArgs {
+    /// Returns the default init args of the VM for the given JNI version, obtained with
+    /// `JNI_GetDefaultJavaVMInitArgs`: the version, without options.
+    ///
+    /// This fails with `JniError::WrongVersion` if the VM does not support the version. JNI 1.1,
+    /// whose init args have a different layout, is never supported.
+    pub fn defaults(version: JNIVersion) -> Result<InitArgs, JvmError> {
+        if version == JNIVersion::V1 {
+            return Err(JvmError::JniCall(errors::JniError::WrongVersion));
+        }
+
+        let mut inner = JavaVMInitArgs {
+            version: version.into(),
+            nOptions: 0,
+            options: ::std::ptr::null_mut(),
+            ignoreUnrecognized: 0,
+        };
+        // Any error code means that the version is not supported (HotSpot returns `JNI_ERR`).
+        if get_default_java_vm_init_args(&mut inner)? != JNI_OK {
+            return Err(JvmError::JniCall(errors::JniError::WrongVersion));
+        }
+
+        Ok(InitArgs {
+            inner,
+            opts: vec![],
+            hooks: Hooks::default(),
+        })
+    }
+
+    /// Returns the JNI version of the init args.
+    pub fn version(&self) -> JNIVersion {
+        self.inner.version.into()
+    }
+
     pub(crate) fn inner_ptr(&self) -> *mut c_void {
         &self.inner as *const _ as _
     }
+
+    /// Makes the hooks of these `InitArgs` the ones called by the trampolines, before launching
+    /// a VM with them.
+    pub(crate) fn install_hooks(&self) {
+        fn install<F: ?Sized>(slot: &RwLock<Option<Arc<F>>>, hook: &Option<Arc<F>>) {
+            match slot.write() {
+                Ok(mut slot) => *slot = hook.clone(),
+                Err(poisoned) => *poisoned.into_inner() = hook.clone(),
+            }
+        }
+
+        install(&VFPRINTF_HOOK, &self.hooks.vfprintf);
+        install(&EXIT_HOOK, &self.hooks.exit);
+        install(&ABORT_HOOK, &self.hooks.abort);
+    }
 }
 
 impl Drop for InitArgs {
@@ -143,3 +589,73 @@ impl Drop for InitArgs {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn invalid_option(builder: InitArgsBuilder) -> &'static str {
+        match builder.build() {
+            Err(JvmError::InvalidOption { option, .. }) => option,
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn test_typed_options() {
+        let builder = InitArgsBuilder::new()
+            .classpath(&["a.jar", "b.jar"])
+            .classpath(&["c.jar"])
+            .system_property("app.name", "a b=c")
+            .max_heap(512 << 20)
+            .max_heap(3 << 30)
+            .max_heap(3_000_001)
+            .add_opens("java.base", "java.lang", "ALL-UNNAMED");
+
+        assert_eq!(
+            builder.options(),
+            vec![
+                "-Djava.class.path=c.jar",
+                "-Dapp.name=a b=c",
+                "-Xmx512m",
+                "-Xmx3g",
+                "-Xmx3000001",
+                "--add-opens=java.base/java.lang=ALL-UNNAMED",
+            ]
+        );
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_typed_options() {
+        let builder = InitArgsBuilder::new().system_property("", "value");
+        assert_eq!(invalid_option(builder), "system property");
+        let builder = InitArgsBuilder::new().system_property("a=b", "value");
+        assert_eq!(invalid_option(builder), "system property");
+        let builder = InitArgsBuilder::new().max_heap(0);
+        assert_eq!(invalid_option(builder), "max heap");
+        let builder = InitArgsBuilder::new().max_heap((2 << 20) - 1);
+        assert_eq!(invalid_option(builder), "max heap");
+        let builder = InitArgsBuilder::new().max_heap(2 << 20);
+        assert!(builder.build().is_ok());
+        let builder = InitArgsBuilder::new().add_opens("java.base", "", "ALL-UNNAMED");
+        assert_eq!(invalid_option(builder), "add-opens");
+        let builder = InitArgsBuilder::new().add_opens("java.base", "java.lang", "a b");
+        assert_eq!(invalid_option(builder), "add-opens");
+        let builder = InitArgsBuilder::new().agent("/nonexistent/libagent.so", None);
+        assert_eq!(invalid_option(builder), "agent");
+
+        let separator = if cfg!(windows) { "a;b.jar" } else { "a:b.jar" };
+        let builder = InitArgsBuilder::new().classpath(&[separator]);
+        assert_eq!(invalid_option(builder), "classpath");
+    }
+
+    #[test]
+    fn test_first_invalid_option_is_reported() {
+        let builder = InitArgsBuilder::new()
+            .max_heap(0)
+            .system_property("", "value");
+        assert_eq!(invalid_option(builder), "max heap");
+    }
+}
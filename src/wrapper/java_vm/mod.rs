@@ -1,7 +1,14 @@
 #[cfg(feature = "invocation")]
+mod argfile;
+#[cfg(feature = "invocation")]
 mod init_args;
 #[cfg(feature = "invocation")]
 pub use self::init_args::*;
 
+#[cfg(feature = "dynamic-invocation")]
+mod dynamic;
+#[cfg(feature = "dynamic-invocation")]
+pub use self::dynamic::*;
+
 mod vm;
 pub use self::vm::*;
@@ -1,8 +1,8 @@
 use std::{
     cell::RefCell,
-    ops::Deref,
+    ops::{Deref, DerefMut},
     ptr,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
     thread::current,
 };
 
@@ -13,6 +13,9 @@ use crate::{errors::*, sys, JNIEnv};
 #[cfg(feature = "invocation")]
 use crate::InitArgs;
 
+#[cfg(feature = "dynamic-invocation")]
+use crate::JvmLibrary;
+
 /// The Java VM, providing [Invocation API][invocation-api] support.
 ///
 /// The JavaVM can be obtained either via [`JNIEnv#get_java_vm`][get-vm] in an already attached
@@ -52,6 +55,9 @@ use crate::InitArgs;
 /// *and* automatic local reference management. Prefer it to manual permanent attaches if
 /// they happen in various parts of the code to reduce the burden of local reference management.
 ///
+/// In asynchronous code, an [`AsyncExecutor`](struct.AsyncExecutor.html) runs closures in a pool
+/// of permanently attached threads instead, and returns futures of their results.
+///
 /// ## Launching JVM from Rust
 ///
 /// To [launch][launch-vm] a JVM from a native process, enable the `invocation` feature
@@ -123,6 +129,21 @@ use crate::InitArgs;
 /// For more information on linking — see documentation
 /// in [build.rs](https://github.com/jni-rs/jni-rs/tree/master/build.rs).
 ///
+/// ### Loading the JVM at run time
+///
+/// With the `dynamic-invocation` feature instead, nothing is linked at build time: the `jvm`
+/// library is loaded when the first VM is launched. It is looked up like during the build
+/// (from `JAVA_HOME`, or from `java` output), unless another library has been
+/// [installed](struct.JvmLibrary.html#method.install) before:
+///
+/// ```rust,ignore
+/// let library = unsafe { JvmLibrary::load("/opt/jdk-17/lib/server/libjvm.so")? };
+/// library.install()?;
+///
+/// let jvm_args = InitArgsBuilder::new().version(JNIVersion::V8).build()?;
+/// let jvm = JavaVM::new(jvm_args)?;
+/// ```
+///
 /// [invocation-api]: https://docs.oracle.com/en/java/javase/12/docs/specs/jni/invocation.html
 /// [get-vm]: struct.JNIEnv.html#method.get_java_vm
 /// [launch-vm]: struct.JavaVM.html#method.new
@@ -143,6 +164,10 @@ impl JavaVM {
     /// not be attached to JVM. You must explicitly use `attach_current_thread…` methods (refer
     /// to [Attaching Native Threads section](#attaching-native-threads)).
     ///
+    /// This first checks with [`InitArgs::defaults`](struct.InitArgs.html#method.defaults) that
+    /// the JNI version of the args is supported by the VM, and fails with
+    /// `JniError::WrongVersion` otherwise.
+    ///
     /// *This API requires "invocation" feature to be enabled,
     /// see ["Launching JVM from Rust"](struct.JavaVM.html#launching-jvm-from-rust).*
     #[cfg(feature = "invocation")]
@@ -152,12 +177,23 @@ impl JavaVM {
         let mut ptr: *mut sys::JavaVM = ::std::ptr::null_mut();
         let mut env: *mut sys::JNIEnv = ::std::ptr::null_mut();
 
+        InitArgs::defaults(args.version())?;
+        args.install_hooks();
+
         unsafe {
-            jni_error_code_to_result(sys::JNI_CreateJavaVM(
+            #[cfg(not(feature = "dynamic-invocation"))]
+            let res = sys::JNI_CreateJavaVM(
+                &mut ptr as *mut _,
+                &mut env as *mut *mut sys::JNIEnv as *mut *mut c_void,
+                args.inner_ptr(),
+            );
+            #[cfg(feature = "dynamic-invocation")]
+            let res = JvmLibrary::get_or_locate()?.create_java_vm(
                 &mut ptr as *mut _,
                 &mut env as *mut *mut sys::JNIEnv as *mut *mut c_void,
                 args.inner_ptr(),
-            ))?;
+            );
+            jni_error_code_to_result(res)?;
 
             let vm = Self::from_raw(ptr)?;
             java_vm_unchecked!(vm.0, DetachCurrentThread);
@@ -166,6 +202,47 @@ impl JavaVM {
         }
     }
 
+    /// Returns the VMs already created in this process, for instance by the application that
+    /// loaded this library as a plugin. HotSpot and most other JVMs support at most one VM
+    /// per process.
+    ///
+    /// Like for VMs [launched](#method.new) from Rust, the current thread must then be attached
+    /// to the VM to use it.
+    ///
+    /// *This API requires "invocation" feature to be enabled,
+    /// see ["Launching JVM from Rust"](struct.JavaVM.html#launching-jvm-from-rust).*
+    #[cfg(feature = "invocation")]
+    pub fn created_vms() -> Result<Vec<Self>> {
+        let mut count: sys::jsize = 0;
+        unsafe { get_created_java_vms(ptr::null_mut(), 0, &mut count)? };
+        if count <= 0 {
+            return Ok(vec![]);
+        }
+
+        let mut vms: Vec<*mut sys::JavaVM> = vec![ptr::null_mut(); count as usize];
+        unsafe { get_created_java_vms(vms.as_mut_ptr(), count, &mut count)? };
+        // A VM may have been destroyed in the meantime.
+        vms.truncate(count.max(0) as usize);
+
+        vms.into_iter()
+            .map(|vm| unsafe { Self::from_raw(vm) })
+            .collect()
+    }
+
+    /// Returns the VM already created in this process, or `Error::NoJavaVM` if there is none.
+    ///
+    /// See [`created_vms`](#method.created_vms).
+    ///
+    /// *This API requires "invocation" feature to be enabled,
+    /// see ["Launching JVM from Rust"](struct.JavaVM.html#launching-jvm-from-rust).*
+    #[cfg(feature = "invocation")]
+    pub fn existing() -> Result<Self> {
+        Self::created_vms()?
+            .into_iter()
+            .next()
+            .ok_or(Error::NoJavaVM)
+    }
+
     /// Create a JavaVM from a raw pointer.
     ///
     /// # Safety
@@ -260,10 +337,45 @@ impl JavaVM {
         ATTACHED_THREADS.load(Ordering::SeqCst)
     }
 
+    /// Destroys the VM, see [Unloading the VM][unload].
+    ///
+    /// The current thread is attached if it is not already, and this method then blocks until
+    /// it is the only non-daemon thread attached to the VM, before the shutdown hooks are run
+    /// and the VM is unloaded. The current thread is detached when it returns.
+    ///
+    /// Once the VM is destroyed, attaching a thread to it fails with `Error::JavaVMDestroyed`,
+    /// and `GlobalRef`s and `WeakRef`s can still be dropped: their references were released
+    /// along with the VM. Note that JVMs generally do not support creating a VM again
+    /// in the same process.
+    ///
+    /// [unload]: https://docs.oracle.com/en/java/javase/12/docs/specs/jni/invocation.html#unloading-the-vm
+    pub fn destroy(self) -> Result<()> {
+        self.attach_current_thread_permanently()?;
+
+        let res = unsafe { java_vm_unchecked!(self.0, DestroyJavaVM) };
+        jni_error_code_to_result(res)?;
+
+        DESTROYED_VM.store(self.0, Ordering::SeqCst);
+        // The VM has already detached this thread.
+        InternalAttachGuard::clear_tls();
+        Ok(())
+    }
+
+    /// Returns `true` if this VM has been [destroyed](#method.destroy).
+    pub fn is_destroyed(&self) -> bool {
+        is_destroyed(self.0)
+    }
+
     /// Get the `JNIEnv` associated with the current thread, or
     /// `ErrorKind::Detached`
     /// if the current thread is not attached to the java VM.
+    ///
+    /// Fails with `Error::JavaVMDestroyed` if the VM has been destroyed.
     pub fn get_env(&self) -> Result<JNIEnv> {
+        if self.is_destroyed() {
+            return Err(Error::JavaVMDestroyed);
+        }
+
         let mut ptr = ptr::null_mut();
         unsafe {
             let res = java_vm_unchecked!(self.0, GetEnv, &mut ptr, sys::JNI_VERSION_1_1);
@@ -275,6 +387,10 @@ impl JavaVM {
 
     /// Creates `InternalAttachGuard` and attaches current thread.
     fn attach_current_thread_impl(&self, thread_type: ThreadType) -> Result<JNIEnv> {
+        if self.is_destroyed() {
+            return Err(Error::JavaVMDestroyed);
+        }
+
         let guard = InternalAttachGuard::new(self.get_java_vm_pointer());
         let env_ptr = unsafe {
             if thread_type == ThreadType::Daemon {
@@ -290,12 +406,43 @@ impl JavaVM {
     }
 }
 
+/// Calls `JNI_GetCreatedJavaVMs`, from the linked or the [loaded](struct.JvmLibrary.html) `jvm`
+/// library. With the `dynamic-invocation` feature, if no library is installed yet, the `jvm`
+/// library already loaded in the process is used before falling back to the one found by
+/// [`JvmLibrary::locate`](struct.JvmLibrary.html#method.locate).
+#[cfg(feature = "invocation")]
+unsafe fn get_created_java_vms(
+    vm_buf: *mut *mut sys::JavaVM,
+    buf_len: sys::jsize,
+    n_vms: *mut sys::jsize,
+) -> Result<()> {
+    #[cfg(not(feature = "dynamic-invocation"))]
+    let res = sys::JNI_GetCreatedJavaVMs(vm_buf, buf_len, n_vms);
+    #[cfg(feature = "dynamic-invocation")]
+    let res = match JvmLibrary::installed() {
+        Some(library) => library.get_created_java_vms(vm_buf, buf_len, n_vms),
+        None => match JvmLibrary::get_created_java_vms_in_process(vm_buf, buf_len, n_vms) {
+            Some(res) => res,
+            None => JvmLibrary::get_or_locate()?.get_created_java_vms(vm_buf, buf_len, n_vms),
+        },
+    };
+    jni_error_code_to_result(res)
+}
+
 thread_local! {
     static THREAD_ATTACH_GUARD: RefCell<Option<InternalAttachGuard>> = RefCell::new(None)
 }
 
 static ATTACHED_THREADS: AtomicUsize = AtomicUsize::new(0);
 
+/// The VM destroyed with `JavaVM::destroy`, if any. As JVMs do not support creating a VM
+/// again once one has been destroyed, a single one is tracked.
+static DESTROYED_VM: AtomicPtr<sys::JavaVM> = AtomicPtr::new(ptr::null_mut());
+
+fn is_destroyed(java_vm: *mut sys::JavaVM) -> bool {
+    DESTROYED_VM.load(Ordering::SeqCst) == java_vm
+}
+
 /// A RAII implementation of scoped guard which detaches the current thread
 /// when dropped. The attached `JNIEnv` can be accessed through this guard
 /// via its `Deref` implementation.
@@ -331,6 +478,12 @@ impl<'a> Deref for AttachGuard<'a> {
     }
 }
 
+impl<'a> DerefMut for AttachGuard<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.env
+    }
+}
+
 impl<'a> Drop for AttachGuard<'a> {
     fn drop(&mut self) {
         if self.should_detach {
@@ -415,8 +568,11 @@ impl InternalAttachGuard {
     }
 
     fn detach(&mut self) -> Result<()> {
-        unsafe {
-            java_vm_unchecked!(self.java_vm, DetachCurrentThread);
+        // Threads can no longer be detached from a destroyed VM, nor need to be.
+        if !is_destroyed(self.java_vm) {
+            unsafe {
+                java_vm_unchecked!(self.java_vm, DetachCurrentThread);
+            }
         }
         ATTACHED_THREADS.fetch_sub(1, Ordering::SeqCst);
         debug!(
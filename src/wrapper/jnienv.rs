@@ -1,28 +1,31 @@
 use std::{
     marker::PhantomData,
     os::raw::{c_char, c_void},
+    panic::{self, AssertUnwindSafe},
     ptr, slice, str,
-    str::FromStr,
     sync::{Mutex, MutexGuard},
+    thread,
 };
 
-use log::warn;
+use log::{error, warn};
 
 use crate::{
     descriptors::Desc,
     errors::*,
     objects::{
-        AutoByteArray, AutoLocal, AutoPrimitiveArray, GlobalRef, JByteBuffer, JClass, JFieldID,
-        JList, JMap, JMethodID, JObject, JStaticFieldID, JStaticMethodID, JString, JThrowable,
-        JValue, ReleaseMode,
+        AutoArray, AutoByteArray, AutoLocal, AutoPrimitiveArray, GlobalRef, JBooleanArray,
+        JByteArray, JByteBuffer, JCharArray, JClass, JDoubleArray, JFieldID, JFloatArray,
+        JIntArray, JList, JLongArray, JMap, JMethodID, JModule, JObject, JObjectRefType,
+        JReflectedField, JReflectedMethod, JShortArray, JStaticFieldID, JStaticMethodID, JString,
+        JThrowable, JValue, PrimitiveArray, ReleaseMode, WeakRef,
     },
-    signature::{JavaType, Primitive, TypeSignature},
-    strings::{JNIString, JavaStr},
+    signature::{AsJavaType, AsMethodSignature, JavaType, Primitive},
+    strings::{JNIString, JavaStr, JavaStrCritical, JavaStrUtf16, MemberName},
     sys::{
-        self, jarray, jboolean, jbooleanArray, jbyte, jbyteArray, jchar, jcharArray, jdouble,
-        jdoubleArray, jfloat, jfloatArray, jint, jintArray, jlong, jlongArray, jobjectArray,
-        jshort, jshortArray, jsize, jvalue, JNINativeMethod,
+        self, jboolean, jbyte, jchar, jdouble, jfieldID, jfloat, jint, jlong, jmethodID,
+        jobjectArray, jshort, jsize, jvalue, JNINativeMethod,
     },
+    wrapper::id_cache::IdCache,
     JNIVersion, JavaVM,
 };
 
@@ -100,6 +103,51 @@ impl<'a> JNIEnv<'a> {
         Ok(jni_unchecked!(self.internal, GetVersion).into())
     }
 
+    /// Fails with `Error::UnsupportedVersion` if the VM does not support the
+    /// `required` JNI version.
+    fn require_version(&self, function: &'static str, required: JNIVersion) -> Result<()> {
+        let actual = self.get_version()?;
+        if actual.is_at_least(required) {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedVersion {
+                function,
+                required,
+                actual,
+            })
+        }
+    }
+
+    /// Get the module a class belongs to.
+    ///
+    /// Requires JNI version 9.
+    pub fn get_module<'c, T>(&self, class: T) -> Result<JModule<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.require_version("get_module", JNIVersion::V9)?;
+        let class = class.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            GetModule,
+            class.into_inner()
+        ))
+    }
+
+    /// Returns true if `thread`, a `java.lang.Thread`, is a virtual thread.
+    ///
+    /// This calls `Thread.isVirtual()`, and so requires JNI version 21.
+    pub fn is_virtual_thread<'b, O>(&self, thread: O) -> Result<bool>
+    where
+        O: Into<JObject<'b>>,
+    {
+        self.require_version("is_virtual_thread", JNIVersion::V21)?;
+        let thread = thread.into();
+        non_null!(thread, "is_virtual_thread thread argument");
+        let thread = JObject::from(thread.into_inner());
+        self.call_method(thread, "isVirtual", "()Z", &[])?.z()
+    }
+
     /// Load a class from a buffer of raw class data. The name of the class must match the name
     /// encoded within the class file data.
     pub fn define_class<S>(&self, name: S, loader: JObject<'a>, buf: &[u8]) -> Result<JClass<'a>>
@@ -213,6 +261,41 @@ impl<'a> JNIEnv<'a> {
         ) == sys::JNI_TRUE)
     }
 
+    /// Returns the kind of the reference `obj`: local, global, weak global or
+    /// invalid. `null` is reported as `JObjectRefType::Invalid`.
+    ///
+    /// Note that a reference that has been deleted may be reported with its
+    /// former kind, or even with the kind of a newer reference reusing the
+    /// same slot, so this is a debugging aid rather than a validity check.
+    pub fn get_object_ref_type<'b, O>(&self, obj: O) -> Result<JObjectRefType>
+    where
+        O: Into<JObject<'b>>,
+    {
+        let ref_type = jni_unchecked!(self.internal, GetObjectRefType, obj.into().into_inner());
+        Ok(ref_type.into())
+    }
+
+    /// In debug builds, panics if the non-null `obj` is not a reference of
+    /// the `expected` kind, to catch references deleted with the wrong
+    /// function before the VM is handed the wrong kind of reference. The
+    /// mismatch is only logged while the thread is already panicking, as a
+    /// second panic would abort. The check is skipped while an exception is
+    /// pending, as `GetObjectRefType` must not be called then.
+    pub(crate) fn debug_assert_ref_type(&self, obj: JObject, expected: JObjectRefType) {
+        if cfg!(debug_assertions) && !obj.is_null() && !self.exception_check().unwrap_or(true) {
+            if let Ok(actual) = self.get_object_ref_type(obj) {
+                if actual != expected {
+                    let message =
+                        format!("deleting a {:?} reference as a {:?} one", actual, expected);
+                    error!("{}", message);
+                    if !thread::panicking() {
+                        panic!("{}", message);
+                    }
+                }
+            }
+        }
+    }
+
     /// Raise an exception from an existing object. This will continue being
     /// thrown in java unless `exception_clear` is called.
     ///
@@ -303,6 +386,182 @@ impl<'a> JNIEnv<'a> {
         Ok(check)
     }
 
+    /// Clear the pending exception, if any, and return it converted to a [`CapturedException`]
+    /// holding its class name, message, stack trace and cause chain.
+    ///
+    /// Returns `Ok(None)` if no exception is pending. If another exception is thrown while the
+    /// details are collected, `Error::JavaException` is returned and that new exception is left
+    /// pending.
+    ///
+    /// [`CapturedException`]: crate::errors::CapturedException
+    pub fn take_exception(&self) -> Result<Option<CapturedException>> {
+        if !self.exception_check()? {
+            return Ok(None);
+        }
+        let throwable = self.exception_occurred()?;
+        self.exception_clear()?;
+
+        self.push_local_frame(16)?;
+        let res = self.capture_throwable(throwable);
+        self.pop_local_frame(JObject::null())?;
+        res.map(Some)
+    }
+
+    /// Replace an `Error::JavaException` with an `Error::CapturedException` holding the
+    /// pending exception, which is cleared. Any other result is returned unchanged.
+    ///
+    /// This is an opt-in alternative to checking for and clearing the exception by hand:
+    ///
+    /// ```rust,ignore
+    /// let res = env.capture_exception(env.call_method(obj, "close", "()V", &[]));
+    /// if let Err(Error::CapturedException(e)) = res {
+    ///     println!("{} at {:?}", e, e.stack_trace.first());
+    /// }
+    /// ```
+    pub fn capture_exception<T>(&self, result: Result<T>) -> Result<T> {
+        match result {
+            Err(Error::JavaException) => match self.take_exception()? {
+                Some(exception) => Err(Error::CapturedException(exception)),
+                None => Err(Error::JavaException),
+            },
+            res => res,
+        }
+    }
+
+    /// Run `f` at the boundary of a native method, turning any failure into a Java exception.
+    ///
+    /// Rust panics must not unwind into the JVM, so native methods (the `Java_...` functions or
+    /// the ones registered with `register_native_methods`) can wrap their body with this method:
+    ///  - if `f` returns `Ok(value)`, `value` is returned;
+    ///  - if `f` returns `Err(e)`, the exception described by `e.to_exception()` is thrown and
+    ///    `default` is returned;
+    ///  - if `f` panics, the panic is caught, a `java.lang.RuntimeException` with the panic
+    ///    message is thrown and `default` is returned.
+    ///
+    /// If a Java exception is already pending when `f` fails (e.g. with
+    /// `Error::JavaException`), it is left pending instead of being replaced, as it is most
+    /// likely the cause of the failure.
+    ///
+    /// Note that `f` is treated as unwind safe: the JVM state is not affected by the panic, but
+    /// any Rust state shared with `f` may be left inconsistent.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// #[no_mangle]
+    /// pub extern "system" fn Java_Foo_parse(env: JNIEnv, _class: JClass, input: JString) -> jint {
+    ///     env.throw_on_failure(0, |env| {
+    ///         let input: String = env.get_string(input)?.into();
+    ///         Ok(input.parse::<jint>().expect("not a number"))
+    ///     })
+    /// }
+    /// ```
+    pub fn throw_on_failure<R, E, F>(&self, default: R, f: F) -> R
+    where
+        F: FnOnce(&JNIEnv<'a>) -> std::result::Result<R, E>,
+        E: ToException,
+    {
+        let exception = match panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(Ok(value)) => return value,
+            Ok(Err(e)) => e.to_exception(),
+            Err(payload) => {
+                let msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+                    (*msg).to_owned()
+                } else if let Some(msg) = payload.downcast_ref::<String>() {
+                    msg.clone()
+                } else {
+                    "Rust panic".to_owned()
+                };
+                Exception {
+                    class: "java/lang/RuntimeException".to_owned(),
+                    msg,
+                }
+            }
+        };
+
+        let res = match self.exception_check() {
+            Ok(true) => Ok(()),
+            _ => self.throw_new(exception.class.as_str(), exception.msg),
+        };
+        if let Err(e) = res {
+            warn!(
+                "Failed to throw an exception at the native method boundary: {}",
+                e
+            );
+        }
+        default
+    }
+
+    pub(crate) fn capture_throwable(&self, throwable: JThrowable<'a>) -> Result<CapturedException> {
+        let mut seen: Vec<JObject<'a>> = Vec::new();
+        let mut chain: Vec<CapturedException> = Vec::new();
+        let mut current: JObject<'a> = throwable.into();
+
+        while !current.is_null() {
+            // Stop on a cyclic cause chain, as `Throwable.printStackTrace` does.
+            for obj in &seen {
+                if self.is_same_object(*obj, current)? {
+                    current = JObject::null();
+                    break;
+                }
+            }
+            if current.is_null() {
+                break;
+            }
+
+            let class = self.get_object_class(current)?;
+            let class_name = self
+                .call_method(class, "getName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let message = self
+                .call_method(current, "getMessage", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let message = if message.is_null() {
+                None
+            } else {
+                Some(self.get_string(message.into())?.into())
+            };
+
+            let trace = self
+                .call_method(
+                    current,
+                    "getStackTrace",
+                    "()[Ljava/lang/StackTraceElement;",
+                    &[],
+                )?
+                .l()?;
+            let len = self.get_array_length(trace)?;
+            let mut stack_trace = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                let element = self.get_object_array_element(trace.into_inner(), i)?;
+                let line = self
+                    .call_method(element, "toString", "()Ljava/lang/String;", &[])?
+                    .l()?;
+                stack_trace.push(self.get_string(line.into())?.into());
+                self.delete_local_ref(line)?;
+                self.delete_local_ref(element)?;
+            }
+
+            chain.push(CapturedException {
+                class: self.get_string(class_name.into())?.into(),
+                message,
+                stack_trace,
+                cause: None,
+            });
+
+            seen.push(current);
+            current = self
+                .call_method(current, "getCause", "()Ljava/lang/Throwable;", &[])?
+                .l()?;
+        }
+
+        let mut exception = chain.pop().ok_or(Error::NullPtr("capture_throwable"))?;
+        while let Some(mut parent) = chain.pop() {
+            parent.cause = Some(Box::new(exception));
+            exception = parent;
+        }
+        Ok(exception)
+    }
+
     /// Create a new instance of a direct java.nio.ByteBuffer.
     pub fn new_direct_byte_buffer(&self, data: &mut [u8]) -> Result<JByteBuffer<'a>> {
         let obj: JObject = jni_non_null_call!(
@@ -347,6 +606,32 @@ impl<'a> JNIEnv<'a> {
         Ok(global)
     }
 
+    /// Creates a new [weak global reference][WeakRef].
+    ///
+    /// If the provided object is null, this method returns `None`. Otherwise, it returns `Some`
+    /// containing the new weak global reference.
+    pub fn new_weak_ref<O>(&self, obj: O) -> Result<Option<WeakRef>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        if obj.is_null() {
+            return Ok(None);
+        }
+
+        let weak: sys::jweak =
+            jni_non_void_call!(self.internal, NewWeakGlobalRef, obj.into_inner());
+
+        // Check if the pointer returned by `NewWeakGlobalRef` is null. This can happen if `obj` is
+        // itself a weak reference that was already garbage collected.
+        if weak.is_null() {
+            return Ok(None);
+        }
+
+        let weak = unsafe { WeakRef::from_raw(self.get_java_vm()?, weak) };
+        Ok(Some(weak))
+    }
+
     /// Create a new local ref to an object.
     ///
     /// Note that the object passed to this is *already* a local ref. This
@@ -388,7 +673,10 @@ impl<'a> JNIEnv<'a> {
     ///
     /// In most cases it is better to use `AutoLocal` (see `auto_local` method)
     /// or `with_local_frame` instead of direct `delete_local_ref` calls.
+    ///
+    /// In debug builds, this panics if `obj` is not a local reference.
     pub fn delete_local_ref(&self, obj: JObject) -> Result<()> {
+        self.debug_assert_ref_type(obj, JObjectRefType::Local);
         jni_unchecked!(self.internal, DeleteLocalRef, obj.into_inner());
         Ok(())
     }
@@ -584,6 +872,16 @@ impl<'a> JNIEnv<'a> {
         }
     }
 
+    /// Clear the cache of method and field IDs used by `call_method`, `get_field` and
+    /// `set_field`.
+    ///
+    /// The cache is shared by all threads. It only holds weak references to the classes it has
+    /// IDs for, so it does not keep them from being unloaded, and the IDs of unloaded classes
+    /// are dropped as new classes are added. Clearing it releases all the entries at once.
+    pub fn clear_id_cache(&self) {
+        IdCache::get().clear();
+    }
+
     /// Look up the static field ID for a class/name/type combination.
     ///
     /// # Example
@@ -628,6 +926,173 @@ impl<'a> JNIEnv<'a> {
         }
     }
 
+    /// Get the method ID of a `java.lang.reflect.Method` or
+    /// `java.lang.reflect.Constructor` object.
+    ///
+    /// Use `from_reflected_static_method` for static methods.
+    pub fn from_reflected_method(&self, method: JReflectedMethod) -> Result<JMethodID<'a>> {
+        non_null!(method, "from_reflected_method method argument");
+        Ok(jni_non_null_call!(
+            self.internal,
+            FromReflectedMethod,
+            method.into_inner()
+        ))
+    }
+
+    /// Get the method ID of a `java.lang.reflect.Method` object for a
+    /// static method.
+    ///
+    /// Returns `Error::NotStatic` if the method is not static.
+    pub fn from_reflected_static_method(
+        &self,
+        method: JReflectedMethod,
+    ) -> Result<JStaticMethodID<'a>> {
+        non_null!(method, "from_reflected_static_method method argument");
+        if !self.is_static_member(*method)? {
+            return Err(Error::NotStatic(
+                "from_reflected_static_method method argument",
+            ));
+        }
+        Ok(jni_non_null_call!(
+            self.internal,
+            FromReflectedMethod,
+            method.into_inner()
+        ))
+    }
+
+    /// Get the field ID of a `java.lang.reflect.Field` object.
+    ///
+    /// Use `from_reflected_static_field` for static fields.
+    pub fn from_reflected_field(&self, field: JReflectedField) -> Result<JFieldID<'a>> {
+        non_null!(field, "from_reflected_field field argument");
+        Ok(jni_non_null_call!(
+            self.internal,
+            FromReflectedField,
+            field.into_inner()
+        ))
+    }
+
+    /// Get the field ID of a `java.lang.reflect.Field` object for a static
+    /// field.
+    ///
+    /// Returns `Error::NotStatic` if the field is not static.
+    pub fn from_reflected_static_field(
+        &self,
+        field: JReflectedField,
+    ) -> Result<JStaticFieldID<'a>> {
+        non_null!(field, "from_reflected_static_field field argument");
+        if !self.is_static_member(*field)? {
+            return Err(Error::NotStatic(
+                "from_reflected_static_field field argument",
+            ));
+        }
+        Ok(jni_non_null_call!(
+            self.internal,
+            FromReflectedField,
+            field.into_inner()
+        ))
+    }
+
+    /// Checks `Modifier.isStatic(member.getModifiers())` for a
+    /// `java.lang.reflect.Member`.
+    fn is_static_member(&self, member: JObject<'a>) -> Result<bool> {
+        let modifiers = self.call_method(member, "getModifiers", "()I", &[])?.i()?;
+        self.call_static_method(
+            "java/lang/reflect/Modifier",
+            "isStatic",
+            "(I)Z",
+            &[JValue::from(modifiers)],
+        )?
+        .z()
+    }
+
+    /// Get the `java.lang.reflect.Method` object, or the
+    /// `java.lang.reflect.Constructor` object for a constructor, of a method
+    /// of `class`.
+    pub fn to_reflected_method<'c, 'm, T, U>(
+        &self,
+        class: T,
+        method_id: U,
+    ) -> Result<JReflectedMethod<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Desc<'a, JMethodID<'m>>,
+    {
+        let class = class.lookup(self)?;
+        let method_id = method_id.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            ToReflectedMethod,
+            class.into_inner(),
+            method_id.into_inner(),
+            sys::JNI_FALSE
+        ))
+    }
+
+    /// Get the `java.lang.reflect.Method` object of a static method of
+    /// `class`.
+    pub fn to_reflected_static_method<'c, 'm, T, U>(
+        &self,
+        class: T,
+        method_id: U,
+    ) -> Result<JReflectedMethod<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Desc<'a, JStaticMethodID<'m>>,
+    {
+        let class = class.lookup(self)?;
+        let method_id = method_id.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            ToReflectedMethod,
+            class.into_inner(),
+            method_id.into_inner(),
+            sys::JNI_TRUE
+        ))
+    }
+
+    /// Get the `java.lang.reflect.Field` object of a field of `class`.
+    pub fn to_reflected_field<'c, 'f, T, U>(
+        &self,
+        class: T,
+        field_id: U,
+    ) -> Result<JReflectedField<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Desc<'a, JFieldID<'f>>,
+    {
+        let class = class.lookup(self)?;
+        let field_id = field_id.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            ToReflectedField,
+            class.into_inner(),
+            field_id.into_inner(),
+            sys::JNI_FALSE
+        ))
+    }
+
+    /// Get the `java.lang.reflect.Field` object of a static field of `class`.
+    pub fn to_reflected_static_field<'c, 'f, T, U>(
+        &self,
+        class: T,
+        field_id: U,
+    ) -> Result<JReflectedField<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Desc<'a, JStaticFieldID<'f>>,
+    {
+        let class = class.lookup(self)?;
+        let field_id = field_id.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            ToReflectedField,
+            class.into_inner(),
+            field_id.into_inner(),
+            sys::JNI_TRUE
+        ))
+    }
+
     /// Get the class for an object.
     pub fn get_object_class<'b, O>(&self, obj: O) -> Result<JClass<'a>>
     where
@@ -775,8 +1240,18 @@ impl<'a> JNIEnv<'a> {
         T: Desc<'a, JMethodID<'m>>,
     {
         let method_id = method_id.lookup(self)?.into_inner();
+        self.call_method_raw(obj.into(), method_id, &ret, args)
+    }
 
-        let obj = obj.into().into_inner();
+    /// `call_method_unchecked`, with a borrowed return type.
+    fn call_method_raw(
+        &self,
+        obj: JObject<'a>,
+        method_id: jmethodID,
+        ret: &JavaType,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        let obj = obj.into_inner();
 
         let args: Vec<jvalue> = args.iter().map(|v| v.to_jni()).collect();
         let jni_args = args.as_ptr();
@@ -835,10 +1310,11 @@ impl<'a> JNIEnv<'a> {
     /// Calls an object method safely. This comes with a number of
     /// lookups/checks. It
     ///
-    /// * Parses the type signature to find the number of arguments and return
-    ///   type
     /// * Looks up the JClass for the given object.
-    /// * Looks up the JMethodID for the class/name/signature combination
+    /// * Parses the type signature to find the number of arguments and return
+    ///   type, and looks up the JMethodID for the class/name/signature
+    ///   combination. Both are cached, so this only happens on the first call
+    ///   for a given class, name and signature (see `clear_id_cache`).
     /// * Ensures that the number of args matches the signature
     /// * Calls `call_method_unchecked` with the verified safe arguments.
     ///
@@ -853,21 +1329,203 @@ impl<'a> JNIEnv<'a> {
     ) -> Result<JValue<'a>>
     where
         O: Into<JObject<'a>>,
-        S: Into<JNIString>,
-        T: Into<JNIString> + AsRef<str>,
+        S: MemberName,
+        T: Into<JNIString> + AsMethodSignature,
     {
         let obj = obj.into();
         non_null!(obj, "call_method obj argument");
 
-        // parse the signature
-        let parsed = TypeSignature::from_str(sig.as_ref())?;
-        if parsed.args.len() != args.len() {
-            return Err(Error::InvalidArgList(parsed));
-        }
-
         let class = self.auto_local(self.get_object_class(obj)?);
+        let method = IdCache::get().method(
+            self,
+            JClass::from(*class.as_obj()),
+            name,
+            &sig,
+            |arg_count| check_cached_arg_count(&sig, arg_count, args),
+        )?;
+
+        self.call_method_raw(obj, method.id, &method.ret, args)
+    }
 
-        self.call_method_unchecked(obj, (&class, name, sig), parsed.ret, args)
+    /// Call a method of the given class on an object, bypassing virtual
+    /// dispatch, in an unsafe manner. This does nothing to check whether the
+    /// method is valid to call on the object, whether the return type is
+    /// correct, or whether the number of args is valid for the method.
+    ///
+    /// This is how a subclass calls the implementation of an overridden method
+    /// in its superclass, like `super.toString()` does in Java.
+    ///
+    /// Under the hood, this simply calls the `CallNonvirtual<Type>MethodA`
+    /// method with the provided arguments.
+    pub fn call_nonvirtual_method_unchecked<'c, 'm, O, T, U>(
+        &self,
+        obj: O,
+        class: T,
+        method_id: U,
+        ret: JavaType,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        T: Desc<'a, JClass<'c>>,
+        U: Desc<'a, JMethodID<'m>>,
+    {
+        let class = class.lookup(self)?;
+        let method_id = method_id.lookup(self)?.into_inner();
+        self.call_nonvirtual_method_raw(obj.into(), class, method_id, &ret, args)
+    }
+
+    /// `call_nonvirtual_method_unchecked`, with a borrowed return type.
+    fn call_nonvirtual_method_raw(
+        &self,
+        obj: JObject<'a>,
+        class: JClass,
+        method_id: jmethodID,
+        ret: &JavaType,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        let class = class.into_inner();
+        let obj = obj.into_inner();
+
+        let args: Vec<jvalue> = args.iter().map(|v| v.to_jni()).collect();
+        let jni_args = args.as_ptr();
+
+        Ok(match ret {
+            JavaType::Object(_) | JavaType::Array(_) => {
+                let obj: JObject = jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualObjectMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into();
+                obj.into()
+            }
+            JavaType::Method(_) => unimplemented!(),
+            JavaType::Primitive(p) => match p {
+                Primitive::Boolean => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualBooleanMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Char => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualCharMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Short => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualShortMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Int => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualIntMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Long => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualLongMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Float => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualFloatMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Double => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualDoubleMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Byte => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualByteMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Void => {
+                    jni_void_call!(
+                        self.internal,
+                        CallNonvirtualVoidMethodA,
+                        obj,
+                        class,
+                        method_id,
+                        jni_args
+                    );
+                    return Ok(JValue::Void);
+                }
+            },
+        })
+    }
+
+    /// Calls a method of the given class on an object safely, bypassing
+    /// virtual dispatch. The checks are the same as in `call_method`, except
+    /// that the method is looked up in `class` rather than in the class of
+    /// the object, which must be an instance of `class`: an
+    /// `Error::NotInstanceOf` is returned otherwise.
+    ///
+    /// Note: this may cause a java exception if the arguments are the wrong
+    /// type, in addition to if the method itself throws.
+    pub fn call_nonvirtual_method<'c, O, T, S, U>(
+        &self,
+        obj: O,
+        class: T,
+        name: S,
+        sig: U,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        T: Desc<'a, JClass<'c>>,
+        S: MemberName,
+        U: Into<JNIString> + AsMethodSignature,
+    {
+        let obj = obj.into();
+        non_null!(obj, "call_nonvirtual_method obj argument");
+
+        let class = class.lookup(self)?;
+        if !self.is_instance_of(obj, class)? {
+            return Err(Error::NotInstanceOf("call_nonvirtual_method obj argument"));
+        }
+        let method = IdCache::get().method(self, class, name, &sig, |arg_count| {
+            check_cached_arg_count(&sig, arg_count, args)
+        })?;
+
+        self.call_nonvirtual_method_raw(obj, class, method.id, &method.ret, args)
     }
 
     /// Calls a static method safely. This comes with a number of
@@ -891,18 +1549,15 @@ impl<'a> JNIEnv<'a> {
     where
         T: Desc<'a, JClass<'c>>,
         U: Into<JNIString>,
-        V: Into<JNIString> + AsRef<str>,
+        V: Into<JNIString> + AsMethodSignature,
     {
-        let parsed = TypeSignature::from_str(&sig)?;
-        if parsed.args.len() != args.len() {
-            return Err(Error::InvalidArgList(parsed));
-        }
+        let ret = check_arg_count(&sig, args)?;
 
         // go ahead and look up the class since it's already Copy,
         // and we'll need that for the next call.
         let class = class.lookup(self)?;
 
-        self.call_static_method_unchecked(class, (class, name, sig), parsed.ret, args)
+        self.call_static_method_unchecked(class, (class, name, sig), ret, args)
     }
 
     /// Create a new object using a constructor. This is done safely using
@@ -915,16 +1570,12 @@ impl<'a> JNIEnv<'a> {
     ) -> Result<JObject<'a>>
     where
         T: Desc<'a, JClass<'c>>,
-        U: Into<JNIString> + AsRef<str>,
+        U: Into<JNIString> + AsMethodSignature,
     {
         // parse the signature
-        let parsed = TypeSignature::from_str(&ctor_sig)?;
-
-        if parsed.args.len() != ctor_args.len() {
-            return Err(Error::InvalidArgList(parsed));
-        }
+        let ret = check_arg_count(&ctor_sig, ctor_args)?;
 
-        if parsed.ret != JavaType::Primitive(Primitive::Void) {
+        if ret != JavaType::Primitive(Primitive::Void) {
             return Err(Error::InvalidCtorReturn);
         }
 
@@ -1027,10 +1678,91 @@ impl<'a> JNIEnv<'a> {
         ))
     }
 
+    /// Get the UTF-16 characters of a JString, without the re-encoding to
+    /// modified UTF-8 done by `get_string`.
+    pub fn get_string_utf16(&self, obj: JString<'a>) -> Result<JavaStrUtf16<'a, '_>> {
+        non_null!(obj, "get_string_utf16 obj argument");
+        JavaStrUtf16::from_env(self, obj)
+    }
+
+    /// Get a pointer to the UTF-16 character array beneath a JString. The
+    /// array is not NUL-terminated, its length is given by `get_string_length`.
+    ///
+    /// # Attention
+    /// This will leak memory if `release_string_chars` is never called.
+    pub fn get_string_chars(&self, obj: JString) -> Result<*const jchar> {
+        non_null!(obj, "get_string_chars obj argument");
+        let ptr: *const jchar = jni_non_null_call!(
+            self.internal,
+            GetStringChars,
+            obj.into_inner(),
+            ::std::ptr::null::<jboolean>() as *mut jboolean
+        );
+        Ok(ptr)
+    }
+
+    /// Unpin the array returned by `get_string_chars`.
+    // It is safe to dereference a pointer that comes from `get_string_chars`.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn release_string_chars(&self, obj: JString, arr: *const jchar) -> Result<()> {
+        non_null!(obj, "release_string_chars obj argument");
+        // This method is safe to call in case of pending exceptions (see the chapter 2 of the spec)
+        jni_unchecked!(self.internal, ReleaseStringChars, obj.into_inner(), arr);
+        Ok(())
+    }
+
+    /// Get the length of a JString, in UTF-16 characters.
+    pub fn get_string_length(&self, obj: JString) -> Result<jsize> {
+        non_null!(obj, "get_string_length obj argument");
+        let len: jsize = jni_unchecked!(self.internal, GetStringLength, obj.into_inner());
+        Ok(len)
+    }
+
+    /// Get the length of a JString in bytes once encoded to java's modified
+    /// UTF-8, not counting the terminating NUL.
+    pub fn get_string_utf_length(&self, obj: JString) -> Result<jsize> {
+        non_null!(obj, "get_string_utf_length obj argument");
+        let len: jsize = jni_unchecked!(self.internal, GetStringUTFLength, obj.into_inner());
+        Ok(len)
+    }
+
+    /// Copy `buf.len()` UTF-16 characters of a JString, starting at `start`,
+    /// into `buf`.
+    ///
+    /// Fails with a `StringIndexOutOfBoundsException` if the region is not
+    /// within the string.
+    pub fn get_string_region(&self, obj: JString, start: jsize, buf: &mut [jchar]) -> Result<()> {
+        non_null!(obj, "get_string_region obj argument");
+        jni_void_call!(
+            self.internal,
+            GetStringRegion,
+            obj.into_inner(),
+            start,
+            buf.len() as jsize,
+            buf.as_mut_ptr()
+        );
+        Ok(())
+    }
+
+    /// Create a new java string object from UTF-16 characters. Unlike
+    /// `new_string`, this involves no re-encoding.
+    pub fn new_string_utf16(&self, from: &[jchar]) -> Result<JString<'a>> {
+        Ok(jni_non_null_call!(
+            self.internal,
+            NewString,
+            from.as_ptr(),
+            from.len() as jsize
+        ))
+    }
+
     /// Get the length of a java array
-    pub fn get_array_length(&self, array: jarray) -> Result<jsize> {
+    pub fn get_array_length<'b, O>(&self, array: O) -> Result<jsize>
+    where
+        O: Into<JObject<'b>>,
+    {
+        let array = array.into();
         non_null!(array, "get_array_length array argument");
-        let len: jsize = jni_unchecked!(self.internal, GetArrayLength, array);
+        let len: jsize = jni_unchecked!(self.internal, GetArrayLength, array.into_inner());
         Ok(len)
     }
 
@@ -1093,13 +1825,13 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Create a new java byte array from a rust byte slice.
-    pub fn byte_array_from_slice(&self, buf: &[u8]) -> Result<jbyteArray> {
+    pub fn byte_array_from_slice(&self, buf: &[u8]) -> Result<JByteArray<'a>> {
         let length = buf.len() as i32;
-        let bytes: jbyteArray = self.new_byte_array(length)?;
+        let bytes = self.new_byte_array(length)?;
         jni_unchecked!(
             self.internal,
             SetByteArrayRegion,
-            bytes,
+            bytes.into_inner(),
             0,
             length,
             buf.as_ptr() as *const i8
@@ -1108,14 +1840,14 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Converts a java byte array to a rust vector of bytes.
-    pub fn convert_byte_array(&self, array: jbyteArray) -> Result<Vec<u8>> {
+    pub fn convert_byte_array(&self, array: JByteArray) -> Result<Vec<u8>> {
         non_null!(array, "convert_byte_array array argument");
-        let length = jni_non_void_call!(self.internal, GetArrayLength, array);
+        let length = jni_non_void_call!(self.internal, GetArrayLength, array.into_inner());
         let mut vec = vec![0u8; length as usize];
         jni_unchecked!(
             self.internal,
             GetByteArrayRegion,
-            array,
+            array.into_inner(),
             0,
             length,
             vec.as_mut_ptr() as *mut i8
@@ -1124,50 +1856,50 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Create a new java boolean array of supplied length.
-    pub fn new_boolean_array(&self, length: jsize) -> Result<jbooleanArray> {
-        let array: jbooleanArray = jni_non_null_call!(self.internal, NewBooleanArray, length);
+    pub fn new_boolean_array(&self, length: jsize) -> Result<JBooleanArray<'a>> {
+        let array: JBooleanArray = jni_non_null_call!(self.internal, NewBooleanArray, length);
         Ok(array)
     }
 
     /// Create a new java byte array of supplied length.
-    pub fn new_byte_array(&self, length: jsize) -> Result<jbyteArray> {
-        let array: jbyteArray = jni_non_null_call!(self.internal, NewByteArray, length);
+    pub fn new_byte_array(&self, length: jsize) -> Result<JByteArray<'a>> {
+        let array: JByteArray = jni_non_null_call!(self.internal, NewByteArray, length);
         Ok(array)
     }
 
     /// Create a new java char array of supplied length.
-    pub fn new_char_array(&self, length: jsize) -> Result<jcharArray> {
-        let array: jcharArray = jni_non_null_call!(self.internal, NewCharArray, length);
+    pub fn new_char_array(&self, length: jsize) -> Result<JCharArray<'a>> {
+        let array: JCharArray = jni_non_null_call!(self.internal, NewCharArray, length);
         Ok(array)
     }
 
     /// Create a new java short array of supplied length.
-    pub fn new_short_array(&self, length: jsize) -> Result<jshortArray> {
-        let array: jshortArray = jni_non_null_call!(self.internal, NewShortArray, length);
+    pub fn new_short_array(&self, length: jsize) -> Result<JShortArray<'a>> {
+        let array: JShortArray = jni_non_null_call!(self.internal, NewShortArray, length);
         Ok(array)
     }
 
     /// Create a new java int array of supplied length.
-    pub fn new_int_array(&self, length: jsize) -> Result<jintArray> {
-        let array: jintArray = jni_non_null_call!(self.internal, NewIntArray, length);
+    pub fn new_int_array(&self, length: jsize) -> Result<JIntArray<'a>> {
+        let array: JIntArray = jni_non_null_call!(self.internal, NewIntArray, length);
         Ok(array)
     }
 
     /// Create a new java long array of supplied length.
-    pub fn new_long_array(&self, length: jsize) -> Result<jlongArray> {
-        let array: jlongArray = jni_non_null_call!(self.internal, NewLongArray, length);
+    pub fn new_long_array(&self, length: jsize) -> Result<JLongArray<'a>> {
+        let array: JLongArray = jni_non_null_call!(self.internal, NewLongArray, length);
         Ok(array)
     }
 
     /// Create a new java float array of supplied length.
-    pub fn new_float_array(&self, length: jsize) -> Result<jfloatArray> {
-        let array: jfloatArray = jni_non_null_call!(self.internal, NewFloatArray, length);
+    pub fn new_float_array(&self, length: jsize) -> Result<JFloatArray<'a>> {
+        let array: JFloatArray = jni_non_null_call!(self.internal, NewFloatArray, length);
         Ok(array)
     }
 
     /// Create a new java double array of supplied length.
-    pub fn new_double_array(&self, length: jsize) -> Result<jdoubleArray> {
-        let array: jdoubleArray = jni_non_null_call!(self.internal, NewDoubleArray, length);
+    pub fn new_double_array(&self, length: jsize) -> Result<JDoubleArray<'a>> {
+        let array: JDoubleArray = jni_non_null_call!(self.internal, NewDoubleArray, length);
         Ok(array)
     }
 
@@ -1182,7 +1914,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_boolean_array_region(
         &self,
-        array: jbooleanArray,
+        array: JBooleanArray,
         start: jsize,
         buf: &mut [jboolean],
     ) -> Result<()> {
@@ -1190,7 +1922,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetBooleanArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1209,7 +1941,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_byte_array_region(
         &self,
-        array: jbyteArray,
+        array: JByteArray,
         start: jsize,
         buf: &mut [jbyte],
     ) -> Result<()> {
@@ -1217,7 +1949,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetByteArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1236,7 +1968,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_char_array_region(
         &self,
-        array: jcharArray,
+        array: JCharArray,
         start: jsize,
         buf: &mut [jchar],
     ) -> Result<()> {
@@ -1244,7 +1976,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetCharArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1263,7 +1995,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_short_array_region(
         &self,
-        array: jshortArray,
+        array: JShortArray,
         start: jsize,
         buf: &mut [jshort],
     ) -> Result<()> {
@@ -1271,7 +2003,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetShortArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1290,7 +2022,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_int_array_region(
         &self,
-        array: jintArray,
+        array: JIntArray,
         start: jsize,
         buf: &mut [jint],
     ) -> Result<()> {
@@ -1298,7 +2030,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetIntArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1317,7 +2049,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_long_array_region(
         &self,
-        array: jlongArray,
+        array: JLongArray,
         start: jsize,
         buf: &mut [jlong],
     ) -> Result<()> {
@@ -1325,7 +2057,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetLongArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1344,7 +2076,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_float_array_region(
         &self,
-        array: jfloatArray,
+        array: JFloatArray,
         start: jsize,
         buf: &mut [jfloat],
     ) -> Result<()> {
@@ -1352,7 +2084,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetFloatArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1371,7 +2103,7 @@ impl<'a> JNIEnv<'a> {
     /// [`array.length`]: struct.JNIEnv.html#method.get_array_length
     pub fn get_double_array_region(
         &self,
-        array: jdoubleArray,
+        array: JDoubleArray,
         start: jsize,
         buf: &mut [jdouble],
     ) -> Result<()> {
@@ -1379,7 +2111,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             GetDoubleArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_mut_ptr()
@@ -1391,7 +2123,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_boolean_array_region(
         &self,
-        array: jbooleanArray,
+        array: JBooleanArray,
         start: jsize,
         buf: &[jboolean],
     ) -> Result<()> {
@@ -1399,7 +2131,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetBooleanArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1411,7 +2143,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_byte_array_region(
         &self,
-        array: jbyteArray,
+        array: JByteArray,
         start: jsize,
         buf: &[jbyte],
     ) -> Result<()> {
@@ -1419,7 +2151,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetByteArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1431,7 +2163,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_char_array_region(
         &self,
-        array: jcharArray,
+        array: JCharArray,
         start: jsize,
         buf: &[jchar],
     ) -> Result<()> {
@@ -1439,7 +2171,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetCharArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1451,7 +2183,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_short_array_region(
         &self,
-        array: jshortArray,
+        array: JShortArray,
         start: jsize,
         buf: &[jshort],
     ) -> Result<()> {
@@ -1459,7 +2191,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetShortArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1469,12 +2201,12 @@ impl<'a> JNIEnv<'a> {
 
     /// Copy the contents of the `buf` slice to the java int array at the
     /// `start` index.
-    pub fn set_int_array_region(&self, array: jintArray, start: jsize, buf: &[jint]) -> Result<()> {
+    pub fn set_int_array_region(&self, array: JIntArray, start: jsize, buf: &[jint]) -> Result<()> {
         non_null!(array, "set_int_array_region array argument");
         jni_void_call!(
             self.internal,
             SetIntArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1486,7 +2218,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_long_array_region(
         &self,
-        array: jlongArray,
+        array: JLongArray,
         start: jsize,
         buf: &[jlong],
     ) -> Result<()> {
@@ -1494,7 +2226,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetLongArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1506,7 +2238,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_float_array_region(
         &self,
-        array: jfloatArray,
+        array: JFloatArray,
         start: jsize,
         buf: &[jfloat],
     ) -> Result<()> {
@@ -1514,7 +2246,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetFloatArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1526,7 +2258,7 @@ impl<'a> JNIEnv<'a> {
     /// `start` index.
     pub fn set_double_array_region(
         &self,
-        array: jdoubleArray,
+        array: JDoubleArray,
         start: jsize,
         buf: &[jdouble],
     ) -> Result<()> {
@@ -1534,7 +2266,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             SetDoubleArrayRegion,
-            array,
+            array.into_inner(),
             start,
             buf.len() as jsize,
             buf.as_ptr()
@@ -1557,6 +2289,16 @@ impl<'a> JNIEnv<'a> {
         non_null!(obj, "get_field_typed obj argument");
 
         let field = field.lookup(self)?.into_inner();
+        self.get_field_raw(obj, field, &ty)
+    }
+
+    /// `get_field_unchecked`, with a borrowed field type.
+    fn get_field_raw(
+        &self,
+        obj: JObject<'a>,
+        field: jfieldID,
+        ty: &JavaType,
+    ) -> Result<JValue<'a>> {
         let obj = obj.into_inner();
 
         // TODO clean this up
@@ -1643,17 +2385,16 @@ impl<'a> JNIEnv<'a> {
     pub fn get_field<O, S, T>(&self, obj: O, name: S, ty: T) -> Result<JValue<'a>>
     where
         O: Into<JObject<'a>>,
-        S: Into<JNIString>,
-        T: Into<JNIString> + AsRef<str>,
+        S: MemberName,
+        T: Into<JNIString> + AsJavaType,
     {
         let obj = obj.into();
         let class = self.auto_local(self.get_object_class(obj)?);
 
-        let parsed = JavaType::from_str(ty.as_ref())?;
+        let field =
+            IdCache::get().field(self, JClass::from(*class.as_obj()), name, &ty, |_| Ok(()))?;
 
-        let field_id: JFieldID = (&class, name, ty).lookup(self)?;
-
-        self.get_field_unchecked(obj, field_id, parsed)
+        self.get_field_raw(obj, field.id, &field.ty)
     }
 
     /// Set a field. Does the same lookups as `get_field` and ensures that the
@@ -1661,36 +2402,38 @@ impl<'a> JNIEnv<'a> {
     pub fn set_field<O, S, T>(&self, obj: O, name: S, ty: T, val: JValue) -> Result<()>
     where
         O: Into<JObject<'a>>,
-        S: Into<JNIString>,
-        T: Into<JNIString> + AsRef<str>,
+        S: MemberName,
+        T: Into<JNIString> + AsJavaType,
     {
         let obj = obj.into();
-        let parsed = JavaType::from_str(ty.as_ref())?;
-        let in_type = val.primitive_type();
+        let class = self.auto_local(self.get_object_class(obj)?);
 
-        match parsed {
-            JavaType::Object(_) | JavaType::Array(_) => {
-                if in_type.is_some() {
-                    return Err(Error::WrongJValueType(val.type_name(), "see java field"));
+        let in_type = val.primitive_type();
+        let check = |field_type: &JavaType| {
+            match *field_type {
+                JavaType::Object(_) | JavaType::Array(_) => {
+                    if in_type.is_some() {
+                        return Err(Error::WrongJValueType(val.type_name(), "see java field"));
+                    }
                 }
-            }
-            JavaType::Primitive(p) => {
-                if let Some(in_p) = in_type {
-                    if in_p == p {
-                        // good
+                JavaType::Primitive(p) => {
+                    if let Some(in_p) = in_type {
+                        if in_p == p {
+                            // good
+                        } else {
+                            return Err(Error::WrongJValueType(val.type_name(), "see java field"));
+                        }
                     } else {
                         return Err(Error::WrongJValueType(val.type_name(), "see java field"));
                     }
-                } else {
-                    return Err(Error::WrongJValueType(val.type_name(), "see java field"));
                 }
+                JavaType::Method(_) => unimplemented!(),
             }
-            JavaType::Method(_) => unimplemented!(),
-        }
-
-        let class = self.auto_local(self.get_object_class(obj)?);
+            Ok(())
+        };
+        let field = IdCache::get().field(self, JClass::from(*class.as_obj()), name, &ty, check)?;
 
-        self.set_field_unchecked(obj, (&class, name, ty), val)
+        self.set_field_unchecked(obj, JFieldID::from(field.id), val)
     }
 
     /// Get a static field without checking the provided type against the actual
@@ -1750,9 +2493,9 @@ impl<'a> JNIEnv<'a> {
     where
         T: Desc<'a, JClass<'c>>,
         U: Into<JNIString>,
-        V: Into<JNIString> + AsRef<str>,
+        V: Into<JNIString> + AsJavaType,
     {
-        let ty = JavaType::from_str(sig.as_ref())?;
+        let ty = sig.as_java_type()?.into_owned();
 
         // go ahead and look up the class since it's already Copy,
         // and we'll need that for the next call.
@@ -1842,7 +2585,7 @@ impl<'a> JNIEnv<'a> {
     pub fn get_rust_field<O, S, T>(&self, obj: O, field: S) -> Result<MutexGuard<T>>
     where
         O: Into<JObject<'a>>,
-        S: Into<JNIString>,
+        S: MemberName,
         T: Send + 'static,
     {
         let obj = obj.into();
@@ -1982,10 +2725,15 @@ impl<'a> JNIEnv<'a> {
     /// release_byte_array_elements() is called.
     ///
     /// See also [`release_byte_array_elements`](struct.JNIEnv.html#method.release_byte_array_elements)
-    pub fn get_byte_array_elements(&self, array: jbyteArray) -> Result<(*mut jbyte, bool)> {
+    pub fn get_byte_array_elements(&self, array: JByteArray) -> Result<(*mut jbyte, bool)> {
         non_null!(array, "get_byte_array_elements array argument");
         let mut is_copy: jboolean = 0xff;
-        let ptr = jni_non_void_call!(self.internal, GetByteArrayElements, array, &mut is_copy);
+        let ptr = jni_non_void_call!(
+            self.internal,
+            GetByteArrayElements,
+            array.into_inner(),
+            &mut is_copy
+        );
         Ok((ptr, is_copy == sys::JNI_TRUE))
     }
 
@@ -2010,7 +2758,7 @@ impl<'a> JNIEnv<'a> {
     /// See also [`commit_byte_array_elements`](struct.JNIEnv.html#method.commit_byte_array_elements)
     pub fn release_byte_array_elements(
         &self,
-        array: jbyteArray,
+        array: JByteArray,
         elems: &mut jbyte,
         mode: ReleaseMode,
     ) -> Result<()> {
@@ -2018,7 +2766,7 @@ impl<'a> JNIEnv<'a> {
         jni_void_call!(
             self.internal,
             ReleaseByteArrayElements,
-            array,
+            array.into_inner(),
             elems,
             mode as i32
         );
@@ -2029,12 +2777,12 @@ impl<'a> JNIEnv<'a> {
     ///
     /// This function has no effect if elems is not a copy of the elements in array. Otherwise,
     /// this function copies back the content of the array (and does not free the elems buffer).
-    pub fn commit_byte_array_elements(&self, array: jbyteArray, elems: &mut jbyte) -> Result<()> {
+    pub fn commit_byte_array_elements(&self, array: JByteArray, elems: &mut jbyte) -> Result<()> {
         non_null!(array, "commit_byte_array_elements array argument");
         jni_void_call!(
             self.internal,
             ReleaseByteArrayElements,
-            array,
+            array.into_inner(),
             elems,
             sys::JNI_COMMIT
         );
@@ -2055,13 +2803,54 @@ impl<'a> JNIEnv<'a> {
     /// See also [`get_byte_array_elements`](struct.JNIEnv.html#method.get_byte_array_elements)
     pub fn get_auto_byte_array_elements(
         &self,
-        array: jbyteArray,
+        array: JByteArray<'a>,
         mode: ReleaseMode,
     ) -> Result<AutoByteArray> {
         let (ptr, is_copy) = self.get_byte_array_elements(array)?;
         AutoByteArray::new(self, array.into(), ptr, mode, is_copy)
     }
 
+    /// Return an AutoArray of the given Java primitive array, of any element type.
+    ///
+    /// This entails a call to the `Get<Type>ArrayElements` JNI function matching the type
+    /// of the array. The result is valid until the AutoArray object goes out of scope, when the
+    /// release happens automatically according to the mode parameter.
+    ///
+    /// Since the returned array may be a copy of the Java array, changes made to the
+    /// returned array will not necessarily be reflected in the original array until
+    /// the AutoArray is dropped.
+    /// AutoArray has a commit() method, to force a copy of the array if needed (and without
+    /// releasing it).
+    ///
+    /// # Safety
+    ///
+    /// The VM may return a pointer to the array itself rather than a copy, which the returned
+    /// `AutoArray` exposes as a mutable slice. The caller must ensure that, as long as the
+    /// `AutoArray` is alive:
+    ///
+    /// * no other `AutoArray`, or other pointer to the elements of the same array, is used;
+    /// * the array is not accessed by any other means, e.g. `set_<type>_array_region`;
+    /// * the array is not modified by Java code, including in other threads.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let array = env.new_double_array(3)?;
+    /// let mut elements = unsafe { env.get_array_elements(array, ReleaseMode::CopyBack)? };
+    /// elements[0] = 1.5;
+    /// ```
+    pub unsafe fn get_array_elements<A>(
+        &self,
+        array: A,
+        mode: ReleaseMode,
+    ) -> Result<AutoArray<'a, '_, A::Elem>>
+    where
+        A: PrimitiveArray<'a>,
+    {
+        let array = array.into();
+        non_null!(array, "get_array_elements array argument");
+        AutoArray::new(self, array, mode)
+    }
+
     /// Return a tuple with a pointer to elements of the given Java primitive array as first
     /// element.
     /// The tuple's second element indicates if the pointed-to array is a copy or not.
@@ -2088,13 +2877,18 @@ impl<'a> JNIEnv<'a> {
     ///
     /// See also [`get_byte_array_elements`](struct.JNIEnv.html#method.get_byte_array_elements)
     /// See also [`release_primitive_array_critical`](struct.JNIEnv.html#method.release_primitive_array_critical)
-    pub fn get_primitive_array_critical(&self, array: jarray) -> Result<(*mut c_void, bool)> {
+    pub fn get_primitive_array_critical<'b, O>(&self, array: O) -> Result<(*mut c_void, bool)>
+    where
+        O: Into<JObject<'b>>,
+    {
+        let array = array.into();
         non_null!(array, "get_primitive_array_critical array argument");
         let mut is_copy: jboolean = 0xff;
-        let ptr = jni_non_void_call!(
+        // No exception check here, as that would be a JNI call within the critical region.
+        let ptr: *mut c_void = jni_unchecked!(
             self.internal,
             GetPrimitiveArrayCritical,
-            array,
+            array.into_inner(),
             &mut is_copy
         );
         non_null!(ptr, "get_primitive_array_critical return value");
@@ -2107,17 +2901,22 @@ impl<'a> JNIEnv<'a> {
     ///
     /// See also [`get_primitive_array_critical`](struct.JNIEnv.html#method.get_primitive_array_critical)
     /// See also [`commit_primitive_array_critical`](struct.JNIEnv.html#method.commit_primitive_array_critical)
-    pub fn release_primitive_array_critical(
+    pub fn release_primitive_array_critical<'b, O>(
         &self,
-        array: jarray,
+        array: O,
         elems: &mut c_void,
         mode: ReleaseMode,
-    ) -> Result<()> {
+    ) -> Result<()>
+    where
+        O: Into<JObject<'b>>,
+    {
+        let array = array.into();
         non_null!(array, "release_primitive_array_critical array argument");
-        jni_void_call!(
+        // This method is safe to call in case of pending exceptions (see the chapter 2 of the spec)
+        jni_unchecked!(
             self.internal,
             ReleasePrimitiveArrayCritical,
-            array,
+            array.into_inner(),
             elems,
             mode as i32
         );
@@ -2128,16 +2927,17 @@ impl<'a> JNIEnv<'a> {
     ///
     /// This function has no effect if elems is not a copy of the elements in array. Otherwise,
     /// this function copies back the content of the array (and does not free the elems buffer).
-    pub fn commit_primitive_array_critical(
-        &self,
-        array: jbyteArray,
-        elems: &mut c_void,
-    ) -> Result<()> {
+    pub fn commit_primitive_array_critical<'b, O>(&self, array: O, elems: &mut c_void) -> Result<()>
+    where
+        O: Into<JObject<'b>>,
+    {
+        let array = array.into();
         non_null!(array, "commit_primitive_array_critical array argument");
-        jni_void_call!(
+        // No exception check here, as the array is still held in the critical region.
+        jni_unchecked!(
             self.internal,
             ReleasePrimitiveArrayCritical,
-            array,
+            array.into_inner(),
             elems,
             sys::JNI_COMMIT
         );
@@ -2164,15 +2964,110 @@ impl<'a> JNIEnv<'a> {
     /// AutoPrimitiveArray also has a commit() method, to force a copy of the array if needed
     /// (without releasing it).
     ///
+    /// The elements are exposed as a `&[T]`/`&mut [T]` slice, whose element type is checked at
+    /// compile time against the type of the array wrapper, and whose length is the array length
+    /// (queried before entering the critical region).
+    ///
+    /// The returned wrapper mutably borrows this `JNIEnv`, so that no other JNI call can be made
+    /// through it until the wrapper is dropped. As `JNIEnv` is `Copy`, this does not apply to
+    /// other copies of the environment, which must not be used in the meantime either.
+    ///
     /// See also [`get_primitive_array_critical`](struct.JNIEnv.html#method.get_primitive_array_critical)
-    pub fn get_auto_primitive_array_critical(
-        &self,
-        array: jarray,
+    pub fn get_auto_primitive_array_critical<A>(
+        &mut self,
+        array: A,
         mode: ReleaseMode,
-    ) -> Result<AutoPrimitiveArray> {
+    ) -> Result<AutoPrimitiveArray<'a, '_, A::Elem>>
+    where
+        A: PrimitiveArray<'a>,
+    {
+        let array = array.into();
+        let len = self.get_array_length(array)? as usize;
         let (ptr, is_copy) = self.get_primitive_array_critical(array)?;
-        AutoPrimitiveArray::new(self, array.into(), ptr, mode, is_copy)
+        unsafe { AutoPrimitiveArray::new(self, array, ptr as *mut A::Elem, len, mode, is_copy) }
+    }
+
+    /// Return a pointer to the UTF-16 characters of a JString, and a boolean indicating whether
+    /// they are a copy. The characters are not NUL-terminated, their number is given by
+    /// `get_string_length`.
+    ///
+    /// This is the string counterpart of `get_primitive_array_critical`, with the same
+    /// restrictions: until `release_string_critical` is called, the code runs in a "critical
+    /// region" where it must not call other JNI functions or block waiting for another Java
+    /// thread. In exchange, the VM is more likely to return the characters without copying them.
+    ///
+    /// See also [`get_auto_string_critical`](struct.JNIEnv.html#method.get_auto_string_critical)
+    pub fn get_string_critical(&self, obj: JString) -> Result<(*const jchar, bool)> {
+        non_null!(obj, "get_string_critical obj argument");
+        let mut is_copy: jboolean = 0xff;
+        // No exception check here, as that would be a JNI call within the critical region.
+        let ptr: *const jchar = jni_unchecked!(
+            self.internal,
+            GetStringCritical,
+            obj.into_inner(),
+            &mut is_copy
+        );
+        non_null!(ptr, "get_string_critical return value");
+        Ok((ptr, is_copy == sys::JNI_TRUE))
+    }
+
+    /// Release the characters returned by `get_string_critical`, leaving the critical region.
+    // It is safe to dereference a pointer that comes from `get_string_critical`.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn release_string_critical(&self, obj: JString, chars: *const jchar) -> Result<()> {
+        non_null!(obj, "release_string_critical obj argument");
+        // This method is safe to call in case of pending exceptions (see the chapter 2 of the spec)
+        jni_unchecked!(
+            self.internal,
+            ReleaseStringCritical,
+            obj.into_inner(),
+            chars
+        );
+        Ok(())
     }
+
+    /// Return a `JavaStrCritical` exposing the UTF-16 characters of a JString as a `&[u16]`,
+    /// without copying them when the VM allows it.
+    ///
+    /// As with `get_auto_primitive_array_critical`, the characters are released when the
+    /// returned guard goes out of scope, and the guard mutably borrows this `JNIEnv` so that no
+    /// other JNI call can be made through it in the meantime. As `JNIEnv` is `Copy`, this does
+    /// not apply to other copies of the environment, which must not be used either. Keep the
+    /// guard alive as briefly as possible.
+    ///
+    /// See also [`get_string_critical`](struct.JNIEnv.html#method.get_string_critical)
+    pub fn get_auto_string_critical(
+        &mut self,
+        obj: JString<'a>,
+    ) -> Result<JavaStrCritical<'a, '_>> {
+        let len = self.get_string_length(obj)? as usize;
+        let (ptr, is_copy) = self.get_string_critical(obj)?;
+        unsafe { JavaStrCritical::new(self, obj, ptr, len, is_copy) }
+    }
+}
+
+/// Checks that the number of arguments matches the method signature, and returns its return
+/// type.
+fn check_arg_count<T: AsMethodSignature>(sig: &T, args: &[JValue]) -> Result<JavaType> {
+    let parsed = sig.as_method_signature()?;
+    if parsed.args.len() != args.len() {
+        return Err(Error::InvalidArgList(parsed.into_owned()));
+    }
+    Ok(parsed.ret.clone())
+}
+
+/// Checks that the number of arguments matches the one of a cached method, only parsing the
+/// signature to report a mismatch.
+fn check_cached_arg_count<T: AsMethodSignature>(
+    sig: &T,
+    arg_count: usize,
+    args: &[JValue],
+) -> Result<()> {
+    if arg_count != args.len() {
+        let parsed = sig.as_method_signature()?.into_owned();
+        return Err(Error::InvalidArgList(parsed));
+    }
+    Ok(())
 }
 
 /// Native method descriptor.
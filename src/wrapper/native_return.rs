@@ -0,0 +1,214 @@
+use std::convert::Infallible;
+
+use crate::{
+    errors::ToException,
+    objects::{
+        JBooleanArray, JByteArray, JByteBuffer, JCharArray, JClass, JCompletableFuture,
+        JDoubleArray, JFloatArray, JIntArray, JList, JLongArray, JMap, JModule, JObject,
+        JReflectedField, JReflectedMethod, JShortArray, JString, JThrowable,
+    },
+    sys::{self, jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort},
+    JNIEnv,
+};
+
+/// Conversion of the value returned by the Rust implementation of a native method to the raw
+/// JNI type returned to the JVM.
+///
+/// This is used by the functions generated by the [`native`](attr.native.html) attribute
+/// macro, which return `<T as NativeReturn>::Raw`: primitives are returned as they are, `bool`
+/// becomes a `jboolean` and the object wrappers are turned into a raw `jobject`. A `Result` of
+/// one of those fails with its error, which is thrown as a Java exception.
+pub trait NativeReturn {
+    /// The raw JNI type returned to the JVM.
+    type Raw;
+
+    /// The error thrown to Java when the conversion fails.
+    type Error: ToException;
+
+    /// The value returned to the JVM when an exception is thrown: zero, `false` or `null`.
+    fn default_raw() -> Self::Raw;
+
+    /// Converts the value to its raw JNI type.
+    fn into_raw(self) -> Result<Self::Raw, Self::Error>;
+}
+
+/// Never thrown, as it cannot be constructed.
+impl ToException for Infallible {
+    fn to_exception(&self) -> crate::errors::Exception {
+        match *self {}
+    }
+}
+
+macro_rules! native_return_raw {
+    ( $($ty:ty => $default:expr),* ) => {
+        $(
+            impl NativeReturn for $ty {
+                type Raw = $ty;
+                type Error = Infallible;
+
+                fn default_raw() -> Self::Raw {
+                    $default
+                }
+
+                fn into_raw(self) -> Result<Self::Raw, Self::Error> {
+                    Ok(self)
+                }
+            }
+        )*
+    };
+}
+
+native_return_raw!(
+    () => (),
+    jboolean => 0,
+    jbyte => 0,
+    jchar => 0,
+    jshort => 0,
+    jint => 0,
+    jlong => 0,
+    jfloat => 0.0,
+    jdouble => 0.0,
+    jobject => std::ptr::null_mut()
+);
+
+impl NativeReturn for bool {
+    type Raw = jboolean;
+    type Error = Infallible;
+
+    fn default_raw() -> Self::Raw {
+        sys::JNI_FALSE
+    }
+
+    fn into_raw(self) -> Result<Self::Raw, Self::Error> {
+        Ok(if self { sys::JNI_TRUE } else { sys::JNI_FALSE })
+    }
+}
+
+impl<T, E> NativeReturn for Result<T, E>
+where
+    T: NativeReturn<Error = Infallible>,
+    E: ToException,
+{
+    type Raw = T::Raw;
+    type Error = E;
+
+    fn default_raw() -> Self::Raw {
+        T::default_raw()
+    }
+
+    fn into_raw(self) -> Result<Self::Raw, Self::Error> {
+        self.map(|value| match value.into_raw() {
+            Ok(raw) => raw,
+            Err(never) => match never {},
+        })
+    }
+}
+
+macro_rules! native_return_object {
+    ( $($ty:ident),* ) => {
+        $(
+            impl<'a> NativeReturn for $ty<'a> {
+                type Raw = jobject;
+                type Error = Infallible;
+
+                fn default_raw() -> Self::Raw {
+                    std::ptr::null_mut()
+                }
+
+                fn into_raw(self) -> Result<Self::Raw, Self::Error> {
+                    Ok(self.into_inner())
+                }
+            }
+
+            // `repr(transparent)` wrappers of a `jobject`.
+            unsafe impl<'a> NativeArg for $ty<'a> {}
+        )*
+    };
+}
+
+native_return_object!(
+    JObject,
+    JString,
+    JClass,
+    JThrowable,
+    JByteBuffer,
+    JReflectedMethod,
+    JReflectedField,
+    JModule,
+    JCompletableFuture,
+    JBooleanArray,
+    JByteArray,
+    JCharArray,
+    JShortArray,
+    JIntArray,
+    JLongArray,
+    JFloatArray,
+    JDoubleArray
+);
+
+impl<'a: 'b, 'b> NativeReturn for JList<'a, 'b> {
+    type Raw = jobject;
+    type Error = Infallible;
+
+    fn default_raw() -> Self::Raw {
+        std::ptr::null_mut()
+    }
+
+    fn into_raw(self) -> Result<Self::Raw, Self::Error> {
+        Ok(JObject::from(self).into_inner())
+    }
+}
+
+impl<'a: 'b, 'b> NativeReturn for JMap<'a, 'b> {
+    type Raw = jobject;
+    type Error = Infallible;
+
+    fn default_raw() -> Self::Raw {
+        std::ptr::null_mut()
+    }
+
+    fn into_raw(self) -> Result<Self::Raw, Self::Error> {
+        Ok(JObject::from(self).into_inner())
+    }
+}
+
+/// Marker trait for the types a native method can take as arguments.
+///
+/// The functions generated by the [`native`](attr.native.html) attribute macro receive their
+/// arguments from the JVM as they are, so each argument type must have the same ABI as the
+/// JNI type the JVM passes: the `JNIEnv`, the `jni::sys` primitive types (and their Rust
+/// equivalents, like `i32` for `jint`), raw `jobject`s and the object wrappers like `JObject`
+/// and `JString`. Other types, like `bool` or `String`, are rejected at compile time:
+///
+/// ```compile_fail
+/// # use jni::{objects::JClass, JNIEnv};
+/// #[jni::native(class = "com/example/Foo")]
+/// fn toggle(_env: JNIEnv, _class: JClass, flag: bool) -> bool {
+///     !flag
+/// }
+/// ```
+///
+/// # Safety
+///
+/// Implementors must be ABI-compatible with the JNI type the JVM passes for the argument,
+/// and valid for any value the JVM may pass, e.g. `#[repr(transparent)]` wrappers of a
+/// `JObject`.
+pub unsafe trait NativeArg {}
+
+unsafe impl<'a> NativeArg for JNIEnv<'a> {}
+
+macro_rules! native_arg_raw {
+    ( $($ty:ty),* ) => {
+        $(
+            unsafe impl NativeArg for $ty {}
+        )*
+    };
+}
+
+native_arg_raw!(jboolean, jbyte, jchar, jshort, jint, jlong, jfloat, jdouble, jobject);
+
+/// Checks, at compile time, that `T` can be taken as an argument by a native method.
+#[doc(hidden)]
+pub fn native_arg<T: NativeArg>(arg: T) -> T {
+    arg
+}
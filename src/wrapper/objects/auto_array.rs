@@ -0,0 +1,226 @@
+use log::debug;
+
+use crate::{
+    errors::*,
+    objects::{
+        JBooleanArray, JByteArray, JCharArray, JDoubleArray, JFloatArray, JIntArray, JLongArray,
+        JObject, JShortArray, ReleaseMode,
+    },
+    sys::{self, jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort},
+    JNIEnv,
+};
+use std::{
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    slice,
+};
+
+/// Trait for the primitive types that can be held in a Java array, associating each one with
+/// the `Get<Type>ArrayElements`/`Release<Type>ArrayElements` pair of JNI functions.
+///
+/// This trait is implemented for `jboolean`, `jbyte`, `jchar`, `jshort`, `jint`, `jlong`,
+/// `jfloat` and `jdouble`, and is not meant to be implemented outside of this crate.
+pub trait TypeArray: Copy + private::Sealed {
+    /// Calls `Get<Type>ArrayElements` on the given array.
+    ///
+    /// # Safety
+    ///
+    /// `array` must be a valid reference to an array of this element type.
+    #[doc(hidden)]
+    unsafe fn get_elements(
+        env: &JNIEnv,
+        array: JObject,
+        is_copy: &mut jboolean,
+    ) -> Result<*mut Self>;
+
+    /// Calls `Release<Type>ArrayElements` on the given array with a raw release mode
+    /// (`0`, `JNI_COMMIT` or `JNI_ABORT`).
+    ///
+    /// # Safety
+    ///
+    /// `elems` must be a pointer returned by `get_elements` for the same array, that has not
+    /// been released yet.
+    #[doc(hidden)]
+    unsafe fn release_elements(
+        env: &JNIEnv,
+        array: JObject,
+        elems: *mut Self,
+        mode: i32,
+    ) -> Result<()>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Trait for the typed array wrappers (`JIntArray`, `JByteArray`, …), naming the primitive type
+/// of their elements. It allows the `JNIEnv` array functions to check at compile time that the
+/// element type matches the array type.
+pub trait PrimitiveArray<'a>: Into<JObject<'a>> + Copy {
+    /// The type of the array elements.
+    type Elem: TypeArray;
+}
+
+macro_rules! type_array {
+    ( $jni_type:ty, $array:ident, $get:ident, $release:ident ) => {
+        impl private::Sealed for $jni_type {}
+
+        impl TypeArray for $jni_type {
+            unsafe fn get_elements(
+                env: &JNIEnv,
+                array: JObject,
+                is_copy: &mut jboolean,
+            ) -> Result<*mut Self> {
+                let internal = env.get_native_interface();
+                let ptr = jni_non_void_call!(internal, $get, array.into_inner(), is_copy);
+                Ok(ptr)
+            }
+
+            unsafe fn release_elements(
+                env: &JNIEnv,
+                array: JObject,
+                elems: *mut Self,
+                mode: i32,
+            ) -> Result<()> {
+                let internal = env.get_native_interface();
+                jni_void_call!(internal, $release, array.into_inner(), elems, mode);
+                Ok(())
+            }
+        }
+
+        impl<'a> PrimitiveArray<'a> for $array<'a> {
+            type Elem = $jni_type;
+        }
+    };
+}
+
+type_array!(
+    jboolean,
+    JBooleanArray,
+    GetBooleanArrayElements,
+    ReleaseBooleanArrayElements
+);
+type_array!(
+    jbyte,
+    JByteArray,
+    GetByteArrayElements,
+    ReleaseByteArrayElements
+);
+type_array!(
+    jchar,
+    JCharArray,
+    GetCharArrayElements,
+    ReleaseCharArrayElements
+);
+type_array!(
+    jshort,
+    JShortArray,
+    GetShortArrayElements,
+    ReleaseShortArrayElements
+);
+type_array!(
+    jint,
+    JIntArray,
+    GetIntArrayElements,
+    ReleaseIntArrayElements
+);
+type_array!(
+    jlong,
+    JLongArray,
+    GetLongArrayElements,
+    ReleaseLongArrayElements
+);
+type_array!(
+    jfloat,
+    JFloatArray,
+    GetFloatArrayElements,
+    ReleaseFloatArrayElements
+);
+type_array!(
+    jdouble,
+    JDoubleArray,
+    GetDoubleArrayElements,
+    ReleaseDoubleArrayElements
+);
+
+/// Auto-release wrapper for pointer-based primitive arrays of any element type.
+///
+/// This wrapper is used to wrap pointers returned by `Get<Type>ArrayElements`, see
+/// [`JNIEnv::get_array_elements`](struct.JNIEnv.html#method.get_array_elements).
+///
+/// These arrays need to be released through a call to `Release<Type>ArrayElements`.
+/// This wrapper provides automatic array release when it goes out of scope, according to
+/// the `ReleaseMode` it was created with. The elements can be accessed as a slice via the
+/// `Deref` and `DerefMut` impls, which is only sound as long as the array is not accessed
+/// otherwise, see the safety section of `get_array_elements`.
+pub struct AutoArray<'a: 'b, 'b, T: TypeArray> {
+    obj: JObject<'a>,
+    ptr: NonNull<T>,
+    len: usize,
+    mode: ReleaseMode,
+    is_copy: bool,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a, 'b, T: TypeArray> AutoArray<'a, 'b, T> {
+    /// Gets the elements of the array and wraps them so that they get released once this
+    /// wrapper goes out of scope.
+    pub(crate) fn new(env: &'b JNIEnv<'a>, obj: JObject<'a>, mode: ReleaseMode) -> Result<Self> {
+        let len = env.get_array_length(obj)? as usize;
+        let mut is_copy: jboolean = 0xff;
+        let ptr = unsafe { T::get_elements(env, obj, &mut is_copy)? };
+        Ok(AutoArray {
+            obj,
+            ptr: NonNull::new(ptr).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            len,
+            mode,
+            is_copy: is_copy == sys::JNI_TRUE,
+            env,
+        })
+    }
+
+    /// Get a reference to the wrapped pointer
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Commits the changes to the array, if it is a copy
+    pub fn commit(&mut self) {
+        let res =
+            unsafe { T::release_elements(self.env, self.obj, self.ptr.as_ptr(), sys::JNI_COMMIT) };
+        match res {
+            Ok(()) => {}
+            Err(e) => debug!("error committing array: {:#?}", e),
+        }
+    }
+
+    /// Indicates if the array is a copy or not
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+}
+
+impl<'a, 'b, T: TypeArray> Deref for AutoArray<'a, 'b, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, 'b, T: TypeArray> DerefMut for AutoArray<'a, 'b, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, 'b, T: TypeArray> Drop for AutoArray<'a, 'b, T> {
+    fn drop(&mut self) {
+        let res =
+            unsafe { T::release_elements(self.env, self.obj, self.ptr.as_ptr(), self.mode as i32) };
+        match res {
+            Ok(()) => {}
+            Err(e) => debug!("error releasing array: {:#?}", e),
+        }
+    }
+}
@@ -62,7 +62,7 @@ impl<'a, 'b> AutoByteArray<'a, 'b> {
     pub fn commit(&mut self) {
         let res = self
             .env
-            .commit_byte_array_elements(*self.obj, unsafe { self.ptr.as_mut() });
+            .commit_byte_array_elements(self.obj.into(), unsafe { self.ptr.as_mut() });
         match res {
             Ok(()) => {}
             Err(e) => debug!("error committing byte array: {:#?}", e),
@@ -78,7 +78,7 @@ impl<'a, 'b> AutoByteArray<'a, 'b> {
 impl<'a, 'b> Drop for AutoByteArray<'a, 'b> {
     fn drop(&mut self) {
         let res = self.env.release_byte_array_elements(
-            *self.obj,
+            self.obj.into(),
             unsafe { self.ptr.as_mut() },
             self.mode,
         );
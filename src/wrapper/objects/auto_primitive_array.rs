@@ -1,9 +1,15 @@
 use log::debug;
 
 use crate::wrapper::objects::ReleaseMode;
-use crate::{errors::*, objects::JObject, JNIEnv};
+use crate::{
+    errors::*,
+    objects::{JObject, TypeArray},
+    JNIEnv,
+};
+use std::ops::{Deref, DerefMut};
 use std::os::raw::c_void;
 use std::ptr::NonNull;
+use std::slice;
 
 /// Auto-release wrapper for pointer-based primitive arrays.
 ///
@@ -12,29 +18,44 @@ use std::ptr::NonNull;
 /// These pointers normally need to be released manually, through a call to
 /// release_primitive_array_critical.
 /// This wrapper provides automatic pointer-based array release when it goes out of scope.
-pub struct AutoPrimitiveArray<'a: 'b, 'b> {
+///
+/// The elements can be accessed as a `&[T]`/`&mut [T]` slice via the `Deref` and `DerefMut` impls.
+///
+/// While this wrapper is alive, the code is in a "critical region" where no other JNI calls
+/// are allowed. To enforce that, the wrapper holds a mutable borrow of the `JNIEnv` it was
+/// created from. Note that `JNIEnv` is `Copy`, so this cannot prevent the use of *other*
+/// copies of the same environment: do not use them until this wrapper is dropped.
+pub struct AutoPrimitiveArray<'a: 'b, 'b, T: TypeArray> {
     obj: JObject<'a>,
-    ptr: NonNull<c_void>,
+    ptr: NonNull<T>,
+    len: usize,
     mode: ReleaseMode,
     is_copy: bool,
-    env: &'b JNIEnv<'a>,
+    env: &'b mut JNIEnv<'a>,
 }
 
-impl<'a, 'b> AutoPrimitiveArray<'a, 'b> {
+impl<'a, 'b, T: TypeArray> AutoPrimitiveArray<'a, 'b, T> {
     /// Creates a new auto-release wrapper for a pointer-based primitive array.
     ///
     /// Once this wrapper goes out of scope, `release_primitive_array_critical` will be
-    /// called on the object. While wrapped, the object can be accessed via the `From` impl.
-    pub fn new(
-        env: &'b JNIEnv<'a>,
+    /// called on the object. While wrapped, the object can be accessed via the `Deref` impl.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer returned by `get_primitive_array_critical` for `obj`, which must
+    /// be an array of `len` elements of type `T`.
+    pub unsafe fn new(
+        env: &'b mut JNIEnv<'a>,
         obj: JObject<'a>,
-        ptr: *mut c_void,
+        ptr: *mut T,
+        len: usize,
         mode: ReleaseMode,
         is_copy: bool,
     ) -> Result<Self> {
         Ok(AutoPrimitiveArray {
             obj,
-            ptr: NonNull::new(ptr).ok_or_else(|| Error::NullPtr("Non-null ptr expected"))?,
+            ptr: NonNull::new(ptr).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            len,
             mode,
             is_copy,
             env,
@@ -42,15 +63,15 @@ impl<'a, 'b> AutoPrimitiveArray<'a, 'b> {
     }
 
     /// Get a reference to the wrapped pointer
-    pub fn as_ptr(&self) -> *mut c_void {
+    pub fn as_ptr(&self) -> *mut T {
         self.ptr.as_ptr()
     }
 
     /// Commits the result of the array, if it is a copy
     pub fn commit(&mut self) {
-        let res = self
-            .env
-            .commit_primitive_array_critical(*self.obj, unsafe { self.ptr.as_mut() });
+        let res = self.env.commit_primitive_array_critical(self.obj, unsafe {
+            &mut *(self.ptr.as_ptr() as *mut c_void)
+        });
         match res {
             Ok(()) => {}
             Err(e) => debug!("error committing primitive array: {:#?}", e),
@@ -63,11 +84,25 @@ impl<'a, 'b> AutoPrimitiveArray<'a, 'b> {
     }
 }
 
-impl<'a, 'b> Drop for AutoPrimitiveArray<'a, 'b> {
+impl<'a, 'b, T: TypeArray> Deref for AutoPrimitiveArray<'a, 'b, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, 'b, T: TypeArray> DerefMut for AutoPrimitiveArray<'a, 'b, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, 'b, T: TypeArray> Drop for AutoPrimitiveArray<'a, 'b, T> {
     fn drop(&mut self) {
         let res = self.env.release_primitive_array_critical(
-            *self.obj,
-            unsafe { self.ptr.as_mut() },
+            self.obj,
+            unsafe { &mut *(self.ptr.as_ptr() as *mut c_void) },
             self.mode,
         );
         match res {
@@ -77,8 +112,8 @@ impl<'a, 'b> Drop for AutoPrimitiveArray<'a, 'b> {
     }
 }
 
-impl<'a> From<&'a AutoPrimitiveArray<'a, '_>> for *mut c_void {
-    fn from(other: &'a AutoPrimitiveArray) -> *mut c_void {
+impl<'a, T: TypeArray> From<&'a AutoPrimitiveArray<'a, '_, T>> for *mut T {
+    fn from(other: &'a AutoPrimitiveArray<T>) -> *mut T {
         other.as_ptr()
     }
 }
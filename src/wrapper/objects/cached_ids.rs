@@ -0,0 +1,157 @@
+use std::sync::OnceLock;
+
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JClass, JMethodID, JObject, JStaticMethodID},
+    signature::{JavaType, MethodSignature},
+    sys::jmethodID,
+    wrapper::id_cache::IdCache,
+    JNIEnv,
+};
+
+/// A class looked up by name the first time it is needed, and then kept in a global reference
+/// shared by all threads.
+///
+/// This is meant to be stored in a `static`, as done by the wrappers generated by the
+/// `java_class!` macro. Since the global reference is never released, the class is never
+/// unloaded, which keeps the IDs of its members valid.
+///
+/// Note that the class is looked up with `FindClass`, and so with the class loader of the
+/// caller of the first lookup.
+pub struct CachedClass {
+    name: &'static str,
+    class: OnceLock<GlobalRef>,
+}
+
+impl CachedClass {
+    /// Creates a cache for the class with the given binary name, with `/` separators.
+    pub const fn new(name: &'static str) -> Self {
+        CachedClass {
+            name,
+            class: OnceLock::new(),
+        }
+    }
+
+    /// Returns the binary name of the class.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the class, looking it up if this is the first call.
+    pub fn get(&self, env: &JNIEnv) -> Result<&GlobalRef> {
+        if let Some(class) = self.class.get() {
+            return Ok(class);
+        }
+
+        let class = env.find_class(self.name)?;
+        let global = env.new_global_ref(class)?;
+        env.delete_local_ref(class.into())?;
+        // If another thread got there first, its reference is kept and this one is released.
+        Ok(self.class.get_or_init(|| global))
+    }
+
+    /// Returns a new local reference to the class, looking it up if this is the first call.
+    pub fn get_local<'a>(&self, env: &JNIEnv<'a>) -> Result<JClass<'a>> {
+        let class = self.get(env)?;
+        let local = env.new_local_ref::<JObject>(JObject::from(class.as_obj().into_inner()))?;
+        Ok(JClass::from(local))
+    }
+}
+
+/// A method ID that can be shared between threads.
+#[derive(Clone, Copy)]
+struct MethodId(jmethodID);
+
+// Method IDs are valid in any thread, as long as their class is not unloaded.
+unsafe impl Send for MethodId {}
+unsafe impl Sync for MethodId {}
+
+/// The ID of an instance method or constructor, looked up the first time it is needed.
+///
+/// Like [`CachedClass`](struct.CachedClass.html), this is meant to be stored in a `static`. The
+/// same `CachedMethodID` must always be used with the same class. The ID is looked up through
+/// the cache used by `JNIEnv::call_method`, so that both share it, and then kept here so that
+/// later calls skip the cache lookup as well.
+pub struct CachedMethodID {
+    name: &'static str,
+    sig: &'static MethodSignature,
+    id: OnceLock<MethodId>,
+}
+
+impl CachedMethodID {
+    /// Creates a cache for the method with the given name and signature. Constructors are
+    /// named `<init>`.
+    pub const fn new(name: &'static str, sig: &'static MethodSignature) -> Self {
+        CachedMethodID {
+            name,
+            sig,
+            id: OnceLock::new(),
+        }
+    }
+
+    /// Returns the signature of the method.
+    pub fn signature(&self) -> &'static MethodSignature {
+        self.sig
+    }
+
+    /// Returns the return type of the method.
+    pub fn ret(&self) -> JavaType {
+        self.sig.signature().ret.clone()
+    }
+
+    /// Returns the ID of the method, looking it up in `class` if this is the first call.
+    pub fn get<'a>(&self, env: &JNIEnv<'a>, class: &CachedClass) -> Result<JMethodID<'a>> {
+        if let Some(id) = self.id.get() {
+            return Ok(JMethodID::from(id.0));
+        }
+
+        let class = JClass::from(*class.get(env)?.as_obj());
+        let method = IdCache::get().method(env, class, self.name, &self.sig, |_| Ok(()))?;
+        let id = self.id.get_or_init(|| MethodId(method.id));
+        Ok(JMethodID::from(id.0))
+    }
+}
+
+/// The ID of a static method, looked up the first time it is needed.
+///
+/// Like [`CachedClass`](struct.CachedClass.html), this is meant to be stored in a `static`. The
+/// same `CachedStaticMethodID` must always be used with the same class. As with
+/// [`CachedMethodID`](struct.CachedMethodID.html), the ID is looked up through the ID cache.
+pub struct CachedStaticMethodID {
+    name: &'static str,
+    sig: &'static MethodSignature,
+    id: OnceLock<MethodId>,
+}
+
+impl CachedStaticMethodID {
+    /// Creates a cache for the static method with the given name and signature.
+    pub const fn new(name: &'static str, sig: &'static MethodSignature) -> Self {
+        CachedStaticMethodID {
+            name,
+            sig,
+            id: OnceLock::new(),
+        }
+    }
+
+    /// Returns the signature of the method.
+    pub fn signature(&self) -> &'static MethodSignature {
+        self.sig
+    }
+
+    /// Returns the return type of the method.
+    pub fn ret(&self) -> JavaType {
+        self.sig.signature().ret.clone()
+    }
+
+    /// Returns the ID of the method, looking it up in `class` if this is the first call.
+    pub fn get<'a>(&self, env: &JNIEnv<'a>, class: &CachedClass) -> Result<JStaticMethodID<'a>> {
+        if let Some(id) = self.id.get() {
+            return Ok(JStaticMethodID::from(id.0));
+        }
+
+        let class = JClass::from(*class.get(env)?.as_obj());
+        let method = IdCache::get().static_method(env, class, self.name, &self.sig, |_| Ok(()))?;
+        let id = self.id.get_or_init(|| MethodId(method.id));
+        Ok(JStaticMethodID::from(id.0))
+    }
+}
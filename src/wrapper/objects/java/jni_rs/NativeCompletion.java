@@ -0,0 +1,22 @@
+package jni_rs;
+
+import java.util.function.BiConsumer;
+
+/**
+ * Completion callback of a {@code CompletableFuture}, completing a Rust future.
+ *
+ * <p>The compiled class is embedded in the {@code jni} crate, which defines it in a class loader
+ * of its own and registers {@link #accept} when it is first needed. Regenerate it with
+ * {@code javac --release 8 NativeCompletion.java} after any change.
+ */
+final class NativeCompletion implements BiConsumer<Object, Throwable> {
+    /** The Rust state to complete, released once by the crate, which resets it to 0. */
+    private long handle;
+
+    NativeCompletion(long handle) {
+        this.handle = handle;
+    }
+
+    @Override
+    public native void accept(Object result, Throwable error);
+}
@@ -0,0 +1,386 @@
+use std::{
+    future::Future,
+    os::raw::c_void,
+    pin::Pin,
+    sync::{Arc, Mutex, OnceLock},
+    task::{Context, Poll, Waker},
+};
+
+use log::debug;
+
+use crate::{
+    descriptors::Desc,
+    errors::*,
+    objects::{GlobalRef, JObject, JThrowable, JValue},
+    sys::{jlong, jobject},
+    JNIEnv, JavaVM, NativeMethod,
+};
+
+/// The binary name of the completion callback class.
+const NATIVE_COMPLETION_CLASS: &str = "jni_rs/NativeCompletion";
+
+/// The completion callback class, compiled from `java/jni_rs/NativeCompletion.java`.
+const NATIVE_COMPLETION_BYTES: &[u8] = include_bytes!("java/jni_rs/NativeCompletion.class");
+
+const COMPLETION_EXCEPTION_CLASS: &str = "java/util/concurrent/CompletionException";
+
+/// Lifetime'd representation of a `java.util.concurrent.CompletableFuture`. Just a `JObject`
+/// wrapped in a new class.
+///
+/// A `JCompletableFuture` can be turned into a Rust `Future` of its result with
+/// [`into_future`](#method.into_future), and one completed by a Rust `Future` can be created
+/// with [`completed_by`](#method.completed_by).
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct JCompletableFuture<'a>(JObject<'a>);
+
+impl<'a> From<jobject> for JCompletableFuture<'a> {
+    fn from(other: jobject) -> Self {
+        JCompletableFuture(From::from(other))
+    }
+}
+
+impl<'a> ::std::ops::Deref for JCompletableFuture<'a> {
+    type Target = JObject<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> From<JCompletableFuture<'a>> for JObject<'a> {
+    fn from(other: JCompletableFuture) -> JObject {
+        other.0
+    }
+}
+
+impl<'a> From<JObject<'a>> for JCompletableFuture<'a> {
+    fn from(other: JObject) -> JCompletableFuture {
+        (other.into_inner()).into()
+    }
+}
+
+impl<'a> JCompletableFuture<'a> {
+    /// Creates a new, incomplete `CompletableFuture`.
+    pub fn new(env: &JNIEnv<'a>) -> Result<Self> {
+        env.new_object("java/util/concurrent/CompletableFuture", "()V", &[])
+            .map(Self::from)
+    }
+
+    /// Completes the future with `value`, if it is not already completed, and returns whether
+    /// this call completed it.
+    pub fn complete<O>(&self, env: &JNIEnv<'a>, value: O) -> Result<bool>
+    where
+        O: Into<JObject<'a>>,
+    {
+        env.call_method(
+            self.0,
+            "complete",
+            "(Ljava/lang/Object;)Z",
+            &[JValue::from(value.into())],
+        )?
+        .z()
+    }
+
+    /// Completes the future with an exception, if it is not already completed, and returns
+    /// whether this call completed it.
+    pub fn complete_exceptionally<T>(&self, env: &JNIEnv<'a>, throwable: T) -> Result<bool>
+    where
+        T: Desc<'a, JThrowable<'a>>,
+    {
+        let throwable = throwable.lookup(env)?;
+        env.call_method(
+            self.0,
+            "completeExceptionally",
+            "(Ljava/lang/Throwable;)Z",
+            &[JValue::from(JObject::from(throwable))],
+        )?
+        .z()
+    }
+
+    /// Returns whether the future is completed, in any way.
+    pub fn is_done(&self, env: &JNIEnv<'a>) -> Result<bool> {
+        env.call_method(self.0, "isDone", "()Z", &[])?.z()
+    }
+
+    /// Returns a Rust `Future` resolving to the result of this one, when it completes.
+    ///
+    /// The result is kept in a `GlobalRef`, which is null if the future completed with `null`.
+    /// If the future completes exceptionally, the `Future` fails with the exception, captured as
+    /// an `Error::CapturedException`. A `CompletionException` is unwrapped to its cause.
+    ///
+    /// This registers a callback with `whenComplete`, which is run by the thread completing the
+    /// future, or by this one if the future is already completed. The callback is only released
+    /// when it runs, so it leaks if the future never completes.
+    pub fn into_future(self, env: &JNIEnv<'a>) -> Result<CompletionFuture> {
+        let class = native_completion_class(env)?;
+        let state = Arc::new(Mutex::new(CompletionState {
+            result: None,
+            waker: None,
+        }));
+
+        let handle = Arc::into_raw(state.clone()) as jlong;
+        let callback = match env.new_object(class, "(J)V", &[JValue::from(handle)]) {
+            Ok(callback) => callback,
+            Err(e) => {
+                drop(unsafe { Arc::from_raw(handle as *const Mutex<CompletionState>) });
+                return Err(e);
+            }
+        };
+        let stage = env
+            .call_method(
+                self.0,
+                "whenComplete",
+                "(Ljava/util/function/BiConsumer;)Ljava/util/concurrent/CompletableFuture;",
+                &[JValue::from(callback)],
+            )
+            .and_then(|stage| stage.l());
+        let stage = match stage {
+            Ok(stage) => stage,
+            Err(e) => {
+                release_callback(env, callback)?;
+                return Err(e);
+            }
+        };
+        env.delete_local_ref(stage)?;
+        env.delete_local_ref(callback)?;
+
+        Ok(CompletionFuture { state })
+    }
+
+    /// Creates a new `CompletableFuture`, completed by `future`, and the `Future` completing it.
+    ///
+    /// The returned `CompletingFuture` must be driven by an async runtime like any other
+    /// `Future`, e.g. spawned on it. When `future` resolves, the `CompletableFuture` is
+    /// completed with its result, or completed exceptionally with a `RuntimeException`
+    /// describing its error (see `ToException`). The thread polling it is attached to the VM for
+    /// that, if it is not already. If the `CompletingFuture` is dropped before, the
+    /// `CompletableFuture` is cancelled.
+    pub fn completed_by<F>(env: &JNIEnv<'a>, future: F) -> Result<(Self, CompletingFuture<F>)>
+    where
+        F: Future<Output = Result<GlobalRef>>,
+    {
+        let completable = Self::new(env)?;
+        let completing = CompletingFuture {
+            future: Box::pin(future),
+            target: Some(env.new_global_ref(completable)?),
+            vm: env.get_java_vm()?,
+        };
+        Ok((completable, completing))
+    }
+}
+
+/// The result of a `CompletableFuture`, set by its completion callback.
+struct CompletionState {
+    result: Option<Result<GlobalRef>>,
+    waker: Option<Waker>,
+}
+
+/// A `Future` resolving to the result of a `CompletableFuture`, see
+/// [`JCompletableFuture::into_future`](struct.JCompletableFuture.html#method.into_future).
+#[must_use = "futures do nothing unless polled"]
+pub struct CompletionFuture {
+    state: Arc<Mutex<CompletionState>>,
+}
+
+impl Future for CompletionFuture {
+    type Output = Result<GlobalRef>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A `Future` completing a `CompletableFuture` with the result of another `Future`, see
+/// [`JCompletableFuture::completed_by`](struct.JCompletableFuture.html#method.completed_by).
+///
+/// It resolves once the `CompletableFuture` is completed, with the error that occurred while
+/// completing it, if any.
+#[must_use = "futures do nothing unless polled"]
+pub struct CompletingFuture<F> {
+    future: Pin<Box<F>>,
+    /// The `CompletableFuture`, until it is completed.
+    target: Option<GlobalRef>,
+    vm: JavaVM,
+}
+
+impl<F> CompletingFuture<F> {
+    /// Completes or cancels the `CompletableFuture`.
+    fn complete(&mut self, result: Option<Result<GlobalRef>>) -> Result<()> {
+        let target = match self.target.take() {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+        let env = self.vm.attach_current_thread()?;
+        let completable = JCompletableFuture::from(target.as_obj());
+
+        let res = match result {
+            Some(Ok(value)) => completable.complete(&env, value.as_obj()).map(drop),
+            Some(Err(e)) => completable
+                .complete_exceptionally(&env, e.to_exception())
+                .map(drop),
+            None => env
+                .call_method(completable.0, "cancel", "(Z)Z", &[JValue::from(false)])
+                .map(drop),
+        };
+        env.capture_exception(res)
+    }
+}
+
+impl<F> Future for CompletingFuture<F>
+where
+    F: Future<Output = Result<GlobalRef>>,
+{
+    type Output = Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if self.target.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        match self.future.as_mut().poll(cx) {
+            Poll::Ready(result) => Poll::Ready(self.complete(Some(result))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F> Drop for CompletingFuture<F> {
+    fn drop(&mut self) {
+        if let Err(e) = self.complete(None) {
+            debug!("error cancelling a CompletableFuture: {:#?}", e);
+        }
+    }
+}
+
+/// Releases the state of a callback that `whenComplete` failed to register, unless it already
+/// ran, and deletes the callback. The pending exception, if any, is kept pending.
+fn release_callback<'a>(env: &JNIEnv<'a>, callback: JObject<'a>) -> Result<()> {
+    let exception = if env.exception_check()? {
+        let exception = env.exception_occurred()?;
+        env.exception_clear()?;
+        Some(exception)
+    } else {
+        None
+    };
+
+    let released = take_handle(env, callback).map(drop);
+    env.delete_local_ref(callback)?;
+    if let Some(exception) = exception {
+        env.throw(exception)?;
+    }
+    released
+}
+
+/// Takes the state of a callback, which is only released once: by the callback when it runs,
+/// or when it could not be registered.
+fn take_handle(env: &JNIEnv, callback: JObject) -> Result<Option<Arc<Mutex<CompletionState>>>> {
+    let _lock = env.lock_obj(callback)?;
+    let handle = env.get_field(callback, "handle", "J")?.j()?;
+    if handle == 0 {
+        return Ok(None);
+    }
+    env.set_field(callback, "handle", "J", JValue::from(0 as jlong))?;
+    Ok(Some(unsafe {
+        Arc::from_raw(handle as *const Mutex<CompletionState>)
+    }))
+}
+
+/// Returns the completion callback class, defining it first if needed.
+///
+/// The class is defined in a class loader of its own, so that other copies of this crate loaded
+/// in the same VM, each with their own native `accept`, define their own class rather than
+/// clash over a single one.
+fn native_completion_class(env: &JNIEnv) -> Result<&'static GlobalRef> {
+    static CLASS: OnceLock<GlobalRef> = OnceLock::new();
+    // Serializes the definition of the class, which can only happen once.
+    static DEFINE: Mutex<()> = Mutex::new(());
+
+    if let Some(class) = CLASS.get() {
+        return Ok(class);
+    }
+    let _define = DEFINE.lock().unwrap();
+    if let Some(class) = CLASS.get() {
+        return Ok(class);
+    }
+
+    let urls = env.auto_local(JObject::from(env.new_object_array(
+        0,
+        "java/net/URL",
+        JObject::null(),
+    )?));
+    // With the bootstrap class loader as parent, like the classes defined without a loader.
+    let loader = env.auto_local(env.new_object(
+        "java/net/URLClassLoader",
+        "([Ljava/net/URL;Ljava/lang/ClassLoader;)V",
+        &[JValue::from(urls.as_obj()), JValue::from(JObject::null())],
+    )?);
+    let class = env.define_class(
+        NATIVE_COMPLETION_CLASS,
+        loader.as_obj(),
+        NATIVE_COMPLETION_BYTES,
+    )?;
+    env.register_native_methods(
+        class,
+        &[NativeMethod {
+            name: "accept".into(),
+            sig: "(Ljava/lang/Object;Ljava/lang/Throwable;)V".into(),
+            fn_ptr: native_accept as *mut c_void,
+        }],
+    )?;
+    let class = env.new_global_ref(class)?;
+    Ok(CLASS.get_or_init(|| class))
+}
+
+/// `NativeCompletion.accept`, called when the `CompletableFuture` completes.
+extern "system" fn native_accept<'a>(
+    env: JNIEnv<'a>,
+    this: JObject<'a>,
+    result: JObject<'a>,
+    error: JThrowable<'a>,
+) {
+    env.throw_on_failure((), |env| {
+        let state = match take_handle(env, this)? {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+
+        let result = if error.is_null() {
+            env.new_global_ref(result)
+        } else {
+            Err(Error::CapturedException(env.capture_throwable(
+                unwrap_completion_exception(env, error)?,
+            )?))
+        };
+
+        let waker = {
+            let mut state = state.lock().unwrap();
+            state.result = Some(result);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok::<_, Error>(())
+    })
+}
+
+/// Returns the cause of `error` if it is a `CompletionException`, and `error` otherwise.
+fn unwrap_completion_exception<'a>(
+    env: &JNIEnv<'a>,
+    error: JThrowable<'a>,
+) -> Result<JThrowable<'a>> {
+    if !env.is_instance_of(error, COMPLETION_EXCEPTION_CLASS)? {
+        return Ok(error);
+    }
+    let cause = env
+        .call_method(error, "getCause", "()Ljava/lang/Throwable;", &[])?
+        .l()?;
+    Ok(if cause.is_null() { error } else { cause.into() })
+}
@@ -0,0 +1,33 @@
+use crate::{objects::JObject, sys::jobject};
+
+/// Lifetime'd representation of a `java.lang.Module`. Just a `JObject`
+/// wrapped in a new class.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct JModule<'a>(JObject<'a>);
+
+impl<'a> From<jobject> for JModule<'a> {
+    fn from(other: jobject) -> Self {
+        JModule(From::from(other))
+    }
+}
+
+impl<'a> ::std::ops::Deref for JModule<'a> {
+    type Target = JObject<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> From<JModule<'a>> for JObject<'a> {
+    fn from(other: JModule) -> JObject {
+        other.0
+    }
+}
+
+impl<'a> From<JObject<'a>> for JModule<'a> {
+    fn from(other: JObject) -> JModule {
+        JModule(other)
+    }
+}
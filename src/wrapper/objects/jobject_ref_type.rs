@@ -0,0 +1,26 @@
+use crate::sys::jobjectRefType;
+
+/// The kind of a reference to a Java object, as returned by
+/// [`JNIEnv::get_object_ref_type`](../struct.JNIEnv.html#method.get_object_ref_type).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JObjectRefType {
+    /// Not a valid reference, e.g. `null` or a deleted reference.
+    Invalid,
+    /// A local reference, valid until it is deleted or its local frame is popped.
+    Local,
+    /// A global reference, see [`GlobalRef`](struct.GlobalRef.html).
+    Global,
+    /// A weak global reference, see [`WeakRef`](struct.WeakRef.html).
+    WeakGlobal,
+}
+
+impl From<jobjectRefType> for JObjectRefType {
+    fn from(other: jobjectRefType) -> Self {
+        match other {
+            jobjectRefType::JNIInvalidRefType => JObjectRefType::Invalid,
+            jobjectRefType::JNILocalRefType => JObjectRefType::Local,
+            jobjectRefType::JNIGlobalRefType => JObjectRefType::Global,
+            jobjectRefType::JNIWeakGlobalRefType => JObjectRefType::WeakGlobal,
+        }
+    }
+}
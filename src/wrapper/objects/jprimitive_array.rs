@@ -0,0 +1,95 @@
+use crate::{
+    objects::JObject,
+    sys::{
+        jbooleanArray, jbyteArray, jcharArray, jdoubleArray, jfloatArray, jintArray, jlongArray,
+        jobject, jshortArray,
+    },
+};
+
+// Defines a lifetime'd wrapper around one of the `j<type>Array` pointer types, with the same
+// set of conversions `JString` and `JClass` provide.
+macro_rules! primitive_array_wrapper {
+    ( $(#[$attr:meta])* $name:ident, $raw:ident ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Debug)]
+        pub struct $name<'a>(JObject<'a>);
+
+        impl<'a> From<$raw> for $name<'a> {
+            fn from(other: $raw) -> Self {
+                $name(From::from(other as jobject))
+            }
+        }
+
+        impl<'a> ::std::ops::Deref for $name<'a> {
+            type Target = JObject<'a>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<'a> From<$name<'a>> for JObject<'a> {
+            fn from(other: $name) -> JObject {
+                other.0
+            }
+        }
+
+        /// This conversion assumes that the `JObject` is a pointer to an array of the
+        /// matching element type.
+        impl<'a> From<JObject<'a>> for $name<'a> {
+            fn from(other: JObject) -> $name {
+                (other.into_inner() as $raw).into()
+            }
+        }
+    };
+}
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jbooleanArray`. Just a `JObject` wrapped in a new
+    /// class.
+    JBooleanArray,
+    jbooleanArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jbyteArray`. Just a `JObject` wrapped in a new class.
+    JByteArray,
+    jbyteArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jcharArray`. Just a `JObject` wrapped in a new class.
+    JCharArray,
+    jcharArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jshortArray`. Just a `JObject` wrapped in a new class.
+    JShortArray,
+    jshortArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jintArray`. Just a `JObject` wrapped in a new class.
+    JIntArray,
+    jintArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jlongArray`. Just a `JObject` wrapped in a new class.
+    JLongArray,
+    jlongArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jfloatArray`. Just a `JObject` wrapped in a new class.
+    JFloatArray,
+    jfloatArray
+);
+
+primitive_array_wrapper!(
+    /// Lifetime'd representation of a `jdoubleArray`. Just a `JObject` wrapped in a new class.
+    JDoubleArray,
+    jdoubleArray
+);
@@ -0,0 +1,33 @@
+use crate::{objects::JObject, sys::jobject};
+
+/// Lifetime'd representation of a `java.lang.reflect.Field`. Just a `JObject`
+/// wrapped in a new class.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct JReflectedField<'a>(JObject<'a>);
+
+impl<'a> From<jobject> for JReflectedField<'a> {
+    fn from(other: jobject) -> Self {
+        JReflectedField(From::from(other))
+    }
+}
+
+impl<'a> ::std::ops::Deref for JReflectedField<'a> {
+    type Target = JObject<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> From<JReflectedField<'a>> for JObject<'a> {
+    fn from(other: JReflectedField) -> JObject {
+        other.0
+    }
+}
+
+impl<'a> From<JObject<'a>> for JReflectedField<'a> {
+    fn from(other: JObject) -> JReflectedField {
+        JReflectedField(other)
+    }
+}
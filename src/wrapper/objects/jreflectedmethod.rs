@@ -0,0 +1,33 @@
+use crate::{objects::JObject, sys::jobject};
+
+/// Lifetime'd representation of a `java.lang.reflect.Method` or a
+/// `java.lang.reflect.Constructor`. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct JReflectedMethod<'a>(JObject<'a>);
+
+impl<'a> From<jobject> for JReflectedMethod<'a> {
+    fn from(other: jobject) -> Self {
+        JReflectedMethod(From::from(other))
+    }
+}
+
+impl<'a> ::std::ops::Deref for JReflectedMethod<'a> {
+    type Target = JObject<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> From<JReflectedMethod<'a>> for JObject<'a> {
+    fn from(other: JReflectedMethod) -> JObject {
+        other.0
+    }
+}
+
+impl<'a> From<JObject<'a>> for JReflectedMethod<'a> {
+    fn from(other: JObject) -> JReflectedMethod {
+        JReflectedMethod(other)
+    }
+}
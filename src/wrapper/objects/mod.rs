@@ -32,13 +32,36 @@ pub use self::jmap::*;
 mod jlist;
 pub use self::jlist::*;
 
+mod jreflectedmethod;
+pub use self::jreflectedmethod::*;
+
+mod jreflectedfield;
+pub use self::jreflectedfield::*;
+
+mod jmodule;
+pub use self::jmodule::*;
+
+mod jcompletable_future;
+pub use self::jcompletable_future::*;
+
 mod jbytebuffer;
 pub use self::jbytebuffer::*;
 
+mod jprimitive_array;
+pub use self::jprimitive_array::*;
+
+// The kinds of references to java objects
+mod jobject_ref_type;
+pub use self::jobject_ref_type::*;
+
 // For storing a reference to a java object
 mod global_ref;
 pub use self::global_ref::*;
 
+// For storing a weak reference to a java object
+mod weak_ref;
+pub use self::weak_ref::*;
+
 // For automatic local ref deletion
 mod auto_local;
 pub use self::auto_local::*;
@@ -47,6 +70,14 @@ pub use self::auto_local::*;
 mod auto_byte_array;
 pub use self::auto_byte_array::*;
 
+// For automatic pointer-based array deletion of any primitive type
+mod auto_array;
+pub use self::auto_array::*;
+
 // For automatic pointer-based primitive array deletion
 mod auto_primitive_array;
 pub use self::auto_primitive_array::*;
+
+// For lazily looked-up classes and method IDs stored in statics
+mod cached_ids;
+pub use self::cached_ids::*;
@@ -1,10 +1,10 @@
-use std::{fmt, str::FromStr};
+use std::{borrow::Cow, fmt, str::FromStr, sync::OnceLock};
 
 use combine::{
     between, many, many1, parser, satisfy, token, ParseError, Parser, StdParseResult, Stream,
 };
 
-use crate::errors::*;
+use crate::{errors::*, strings::JNIString};
 
 /// A primitive java type. These are the things that can be represented without
 /// an object.
@@ -105,6 +105,168 @@ impl fmt::Display for TypeSignature {
     }
 }
 
+/// A method signature checked at compile time by the [`sig!`](../macro.sig.html) macro.
+///
+/// It holds the signature string along with the code building its `TypeSignature`, which is
+/// run once, on first use, instead of parsing the string.
+pub struct MethodSignature {
+    sig: &'static str,
+    parsed: OnceLock<TypeSignature>,
+    build: fn() -> TypeSignature,
+}
+
+impl MethodSignature {
+    #[doc(hidden)]
+    pub const fn __new(sig: &'static str, build: fn() -> TypeSignature) -> Self {
+        MethodSignature {
+            sig,
+            parsed: OnceLock::new(),
+            build,
+        }
+    }
+
+    /// Returns the signature string, e.g. `(ILjava/lang/String;)Z`.
+    pub fn as_str(&self) -> &'static str {
+        self.sig
+    }
+
+    /// Returns the parsed signature.
+    pub fn signature(&self) -> &TypeSignature {
+        self.parsed.get_or_init(self.build)
+    }
+}
+
+impl fmt::Debug for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("MethodSignature").field(&self.sig).finish()
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.sig)
+    }
+}
+
+impl From<&MethodSignature> for JNIString {
+    fn from(other: &MethodSignature) -> Self {
+        other.sig.into()
+    }
+}
+
+/// A field type checked at compile time by the [`sig!`](../macro.sig.html) macro.
+///
+/// It holds the type descriptor string along with the code building its `JavaType`, which is
+/// run once, on first use, instead of parsing the string.
+pub struct FieldSignature {
+    sig: &'static str,
+    parsed: OnceLock<JavaType>,
+    build: fn() -> JavaType,
+}
+
+impl FieldSignature {
+    #[doc(hidden)]
+    pub const fn __new(sig: &'static str, build: fn() -> JavaType) -> Self {
+        FieldSignature {
+            sig,
+            parsed: OnceLock::new(),
+            build,
+        }
+    }
+
+    /// Returns the type descriptor string, e.g. `Ljava/lang/String;`.
+    pub fn as_str(&self) -> &'static str {
+        self.sig
+    }
+
+    /// Returns the parsed type.
+    pub fn java_type(&self) -> &JavaType {
+        self.parsed.get_or_init(self.build)
+    }
+}
+
+impl fmt::Debug for FieldSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FieldSignature").field(&self.sig).finish()
+    }
+}
+
+impl fmt::Display for FieldSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.sig)
+    }
+}
+
+impl From<&FieldSignature> for JNIString {
+    fn from(other: &FieldSignature) -> Self {
+        other.sig.into()
+    }
+}
+
+/// A method signature accepted by the checked `JNIEnv` methods, like `call_method`.
+///
+/// Strings are parsed on every call, while the [`MethodSignature`]s built by the `sig!` macro
+/// are not parsed at all.
+pub trait AsMethodSignature {
+    /// Returns the signature string.
+    fn signature_str(&self) -> &str;
+
+    /// Returns the parsed method signature.
+    fn as_method_signature(&self) -> Result<Cow<'_, TypeSignature>>;
+}
+
+impl<T: AsRef<str> + ?Sized> AsMethodSignature for T {
+    fn signature_str(&self) -> &str {
+        self.as_ref()
+    }
+
+    fn as_method_signature(&self) -> Result<Cow<'_, TypeSignature>> {
+        TypeSignature::from_str(self.as_ref()).map(Cow::Owned)
+    }
+}
+
+impl AsMethodSignature for &MethodSignature {
+    fn signature_str(&self) -> &str {
+        self.sig
+    }
+
+    fn as_method_signature(&self) -> Result<Cow<'_, TypeSignature>> {
+        Ok(Cow::Borrowed(self.signature()))
+    }
+}
+
+/// A field type accepted by the checked `JNIEnv` methods, like `get_field`.
+///
+/// Strings are parsed on every call, while the [`FieldSignature`]s built by the `sig!` macro
+/// are not parsed at all.
+pub trait AsJavaType {
+    /// Returns the type descriptor string.
+    fn signature_str(&self) -> &str;
+
+    /// Returns the parsed field type.
+    fn as_java_type(&self) -> Result<Cow<'_, JavaType>>;
+}
+
+impl<T: AsRef<str> + ?Sized> AsJavaType for T {
+    fn signature_str(&self) -> &str {
+        self.as_ref()
+    }
+
+    fn as_java_type(&self) -> Result<Cow<'_, JavaType>> {
+        JavaType::from_str(self.as_ref()).map(Cow::Owned)
+    }
+}
+
+impl AsJavaType for &FieldSignature {
+    fn signature_str(&self) -> &str {
+        self.sig
+    }
+
+    fn as_java_type(&self) -> Result<Cow<'_, JavaType>> {
+        Ok(Cow::Borrowed(self.java_type()))
+    }
+}
+
 fn parse_primitive<S: Stream<Token = char>>(input: &mut S) -> StdParseResult<JavaType, S>
 where
     S::Error: ParseError<char, S::Range, S::Position>,
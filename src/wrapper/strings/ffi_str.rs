@@ -12,6 +12,7 @@ use crate::wrapper::strings::ffi_str;
 /// Wrapper for `std::ffi::CString` that also takes care of encoding between
 /// UTF-8 and Java's Modified UTF-8. As with `CString`, this implements `Deref`
 /// to `&JNIStr`.
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct JNIString {
     internal: ffi::CString,
 }
@@ -50,6 +51,30 @@ where
     }
 }
 
+/// The name of a method or field, as taken by the checked `JNIEnv` methods like `call_method`.
+///
+/// Besides converting it to a `JNIString`, this gives access to the name as a Rust string, so
+/// that it can be looked up in the ID cache without being converted first.
+pub trait MemberName: Into<JNIString> {
+    /// Returns the name as a Rust string.
+    fn as_name(&self) -> Cow<'_, str>;
+}
+
+impl<T> MemberName for T
+where
+    T: AsRef<str>,
+{
+    fn as_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_ref())
+    }
+}
+
+impl MemberName for JNIString {
+    fn as_name(&self) -> Cow<'_, str> {
+        Cow::from(self.borrowed())
+    }
+}
+
 impl<'a> From<&'a JNIStr> for Cow<'a, str> {
     fn from(other: &'a JNIStr) -> Cow<'a, str> {
         let bytes = other.to_bytes();
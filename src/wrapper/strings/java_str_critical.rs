@@ -0,0 +1,85 @@
+use std::{char::DecodeUtf16Error, ops::Deref, ptr::NonNull, slice};
+
+use log::debug;
+
+use crate::{errors::*, objects::JString, strings::decode_utf16, sys::jchar, JNIEnv};
+
+/// Auto-release wrapper for the characters of a string returned by GetStringCritical.
+///
+/// The characters can be read as a `&[u16]` slice via the `Deref` impl. When the VM
+/// allows it, they are not copied. ReleaseStringCritical is called on Drop.
+///
+/// While this wrapper is alive, the code is in a "critical region" where no other JNI calls
+/// are allowed. To enforce that, the wrapper holds a mutable borrow of the `JNIEnv` it was
+/// created from. Note that `JNIEnv` is `Copy`, so this cannot prevent the use of *other*
+/// copies of the same environment: do not use them until this wrapper is dropped.
+pub struct JavaStrCritical<'a: 'b, 'b> {
+    obj: JString<'a>,
+    ptr: NonNull<jchar>,
+    len: usize,
+    is_copy: bool,
+    env: &'b mut JNIEnv<'a>,
+}
+
+impl<'a, 'b> JavaStrCritical<'a, 'b> {
+    /// Creates a new auto-release wrapper for the characters of a string.
+    ///
+    /// Once this wrapper goes out of scope, `release_string_critical` will be called on the
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer returned by `get_string_critical` for `obj`, which must be a
+    /// string of `len` UTF-16 characters.
+    pub unsafe fn new(
+        env: &'b mut JNIEnv<'a>,
+        obj: JString<'a>,
+        ptr: *const jchar,
+        len: usize,
+        is_copy: bool,
+    ) -> Result<Self> {
+        Ok(JavaStrCritical {
+            obj,
+            ptr: NonNull::new(ptr as *mut jchar).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            len,
+            is_copy,
+            env,
+        })
+    }
+
+    /// Get the wrapped pointer
+    pub fn as_ptr(&self) -> *const jchar {
+        self.ptr.as_ptr()
+    }
+
+    /// Indicates if the characters are a copy or not
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    /// Decodes the characters into a `String`, failing on unpaired
+    /// surrogates, which Java strings may contain.
+    pub fn try_to_string(&self) -> ::std::result::Result<String, DecodeUtf16Error> {
+        decode_utf16(self).collect()
+    }
+}
+
+impl<'a, 'b> Deref for JavaStrCritical<'a, 'b> {
+    type Target = [jchar];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, 'b> Drop for JavaStrCritical<'a, 'b> {
+    fn drop(&mut self) {
+        let res = self
+            .env
+            .release_string_critical(self.obj, self.ptr.as_ptr());
+        match res {
+            Ok(()) => {}
+            Err(e) => debug!("error releasing critical string: {:#?}", e),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::{char::DecodeUtf16Error, ops::Deref, slice};
+
+use log::warn;
+
+use crate::{errors::*, objects::JString, sys::jchar, JNIEnv};
+
+/// Reference to the UTF-16 characters of a string in the JVM. Holds a pointer
+/// to the array returned by GetStringChars. Calls ReleaseStringChars on Drop.
+///
+/// Unlike [`JavaStr`](struct.JavaStr.html), this gives access to the characters
+/// as Java stores them, as a `&[u16]` slice via the `Deref` impl, without any
+/// re-encoding to modified UTF-8.
+pub struct JavaStrUtf16<'a: 'b, 'b> {
+    internal: *const jchar,
+    len: usize,
+    obj: JString<'a>,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a: 'b, 'b> JavaStrUtf16<'a, 'b> {
+    /// Build a `JavaStrUtf16` from an object and a reference to the environment.
+    /// You probably want to use `JNIEnv::get_string_utf16` instead.
+    pub fn from_env(env: &'b JNIEnv<'a>, obj: JString<'a>) -> Result<Self> {
+        let len = env.get_string_length(obj)? as usize;
+        let ptr = env.get_string_chars(obj)?;
+        Ok(JavaStrUtf16 {
+            internal: ptr,
+            len,
+            obj,
+            env,
+        })
+    }
+
+    /// Extract the raw pointer to the UTF-16 characters. Note that they are
+    /// not NUL-terminated.
+    pub fn get_raw(&self) -> *const jchar {
+        self.internal
+    }
+
+    /// Decodes the characters into a `String`, failing on unpaired
+    /// surrogates, which Java strings may contain.
+    pub fn try_to_string(&self) -> ::std::result::Result<String, DecodeUtf16Error> {
+        decode_utf16(self).collect()
+    }
+}
+
+/// Decodes UTF-16 characters, e.g. from a region copied with
+/// `JNIEnv::get_string_region`, into `char`s.
+pub fn decode_utf16(
+    chars: &[jchar],
+) -> impl Iterator<Item = ::std::result::Result<char, DecodeUtf16Error>> + '_ {
+    ::std::char::decode_utf16(chars.iter().copied())
+}
+
+impl<'a: 'b, 'b> Deref for JavaStrUtf16<'a, 'b> {
+    type Target = [jchar];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.internal, self.len) }
+    }
+}
+
+/// Unpaired surrogates are replaced with `U+FFFD REPLACEMENT CHARACTER`, see
+/// `try_to_string` for a checked conversion.
+impl<'a: 'b, 'b: 'c, 'c> From<&'c JavaStrUtf16<'a, 'b>> for String {
+    fn from(other: &'c JavaStrUtf16) -> String {
+        decode_utf16(other)
+            .map(|c| c.unwrap_or(::std::char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+}
+
+/// Unpaired surrogates are replaced with `U+FFFD REPLACEMENT CHARACTER`, see
+/// `try_to_string` for a checked conversion.
+impl<'a: 'b, 'b> From<JavaStrUtf16<'a, 'b>> for String {
+    fn from(other: JavaStrUtf16) -> String {
+        (&other).into()
+    }
+}
+
+impl<'a: 'b, 'b> Drop for JavaStrUtf16<'a, 'b> {
+    fn drop(&mut self) {
+        match self.env.release_string_chars(self.obj, self.internal) {
+            Ok(()) => {}
+            Err(e) => warn!("error dropping java str: {}", e),
+        }
+    }
+}
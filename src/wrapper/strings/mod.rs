@@ -4,3 +4,9 @@ pub use self::ffi_str::*;
 
 mod java_str;
 pub use self::java_str::*;
+
+mod java_str_utf16;
+pub use self::java_str_utf16::*;
+
+mod java_str_critical;
+pub use self::java_str_critical::*;
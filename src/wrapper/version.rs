@@ -1,11 +1,12 @@
 use crate::sys::{
-    JNI_VERSION_1_1, JNI_VERSION_1_2, JNI_VERSION_1_4, JNI_VERSION_1_6, JNI_VERSION_1_8,
+    JNI_VERSION_10, JNI_VERSION_19, JNI_VERSION_1_1, JNI_VERSION_1_2, JNI_VERSION_1_4,
+    JNI_VERSION_1_6, JNI_VERSION_1_8, JNI_VERSION_20, JNI_VERSION_21, JNI_VERSION_9,
 };
 
 /// JNI Version
 ///
-/// This maps to the `jni_sys::JNI_VERSION_1_*` constants.
-#[derive(Debug, Copy, Clone)]
+/// This maps to the `jni_sys::JNI_VERSION_*` constants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(missing_docs)]
 pub enum JNIVersion {
     V1,
@@ -13,9 +14,24 @@ pub enum JNIVersion {
     V4,
     V6,
     V8,
+    V9,
+    V10,
+    V19,
+    V20,
+    V21,
     Invalid(i32),
 }
 
+impl JNIVersion {
+    /// Returns true if this version is `other` or a newer one.
+    ///
+    /// Versions are compared by their `JNI_VERSION_*` value, so an unknown
+    /// (`Invalid`) version newer than the ones listed here also qualifies.
+    pub fn is_at_least(self, other: JNIVersion) -> bool {
+        i32::from(self) >= i32::from(other)
+    }
+}
+
 impl From<i32> for JNIVersion {
     fn from(other: i32) -> Self {
         match other {
@@ -24,6 +40,11 @@ impl From<i32> for JNIVersion {
             JNI_VERSION_1_4 => JNIVersion::V4,
             JNI_VERSION_1_6 => JNIVersion::V6,
             JNI_VERSION_1_8 => JNIVersion::V8,
+            JNI_VERSION_9 => JNIVersion::V9,
+            JNI_VERSION_10 => JNIVersion::V10,
+            JNI_VERSION_19 => JNIVersion::V19,
+            JNI_VERSION_20 => JNIVersion::V20,
+            JNI_VERSION_21 => JNIVersion::V21,
             v => JNIVersion::Invalid(v),
         }
     }
@@ -37,6 +58,11 @@ impl From<JNIVersion> for i32 {
             JNIVersion::V4 => JNI_VERSION_1_4,
             JNIVersion::V6 => JNI_VERSION_1_6,
             JNIVersion::V8 => JNI_VERSION_1_8,
+            JNIVersion::V9 => JNI_VERSION_9,
+            JNIVersion::V10 => JNI_VERSION_10,
+            JNIVersion::V19 => JNI_VERSION_19,
+            JNIVersion::V20 => JNI_VERSION_20,
+            JNIVersion::V21 => JNI_VERSION_21,
             JNIVersion::Invalid(v) => v,
         }
     }
@@ -0,0 +1,117 @@
+#![cfg(feature = "invocation")]
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use jni::{errors::Error, objects::JValue, sys::jint, AsyncExecutor, AsyncExecutorBuilder};
+
+mod util;
+use util::{block_on, jvm, poll_once};
+
+#[test]
+fn async_executor_runs_tasks() {
+    let executor = AsyncExecutor::new(jvm().clone(), 2).unwrap();
+    assert_eq!(executor.threads(), 2);
+
+    let futures: Vec<_> = (0..10)
+        .map(|i| {
+            executor.spawn(move |env| {
+                let name = thread::current().name().unwrap().to_owned();
+                assert!(name.starts_with("jni-async-"));
+                let x = JValue::from(-i as jint);
+                env.call_static_method("java/lang/Math", "abs", "(I)I", &[x])?
+                    .i()
+            })
+        })
+        .collect();
+    for (i, future) in futures.into_iter().enumerate() {
+        assert_eq!(block_on(future).unwrap(), i as jint);
+    }
+
+    executor.shutdown();
+}
+
+#[test]
+fn async_executor_isolates_tasks() {
+    let executor = AsyncExecutor::new(jvm().clone(), 1).unwrap();
+
+    let res = block_on(executor.spawn(|env| {
+        let input = JValue::from(env.new_string("not a number")?);
+        env.call_static_method(
+            "java/lang/Integer",
+            "parseInt",
+            "(Ljava/lang/String;)I",
+            &[input],
+        )?
+        .i()
+    }));
+    assert!(matches!(res, Err(Error::JavaException)));
+    // The exception has been cleared.
+    assert!(!block_on(executor.spawn(|env| env.exception_check())).unwrap());
+
+    let panicking = executor.spawn(|_| -> jni::errors::Result<()> { panic!("in a task") });
+    let res = panic::catch_unwind(AssertUnwindSafe(|| block_on(panicking)));
+    assert!(res.is_err());
+    // The thread survives panics.
+    assert_eq!(block_on(executor.spawn(|_| Ok(42))).unwrap(), 42);
+}
+
+#[test]
+fn async_executor_applies_backpressure() {
+    let executor = AsyncExecutorBuilder::new(jvm().clone())
+        .threads(1)
+        .queue_capacity(1)
+        .build()
+        .unwrap();
+
+    let (started_tx, started_rx) = mpsc::channel();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let mut running = executor.spawn(move |_| {
+        started_tx.send(()).unwrap();
+        release_rx.recv().unwrap();
+        Ok(1)
+    });
+    assert!(poll_once(&mut running).is_pending());
+    started_rx.recv().unwrap();
+
+    let mut queued = executor.spawn(|_| Ok(2));
+    assert!(poll_once(&mut queued).is_pending());
+    let mut blocked = executor.spawn(|_| Ok(3));
+    assert!(poll_once(&mut blocked).is_pending());
+
+    release_tx.send(()).unwrap();
+    assert_eq!(block_on(running).unwrap(), 1);
+    assert_eq!(block_on(queued).unwrap(), 2);
+    assert_eq!(block_on(blocked).unwrap(), 3);
+}
+
+#[test]
+fn async_executor_shuts_down_gracefully() {
+    let executor = AsyncExecutor::new(jvm().clone(), 2).unwrap();
+
+    let mut queued = executor.spawn(|_| Ok("done"));
+    let _ = poll_once(&mut queued);
+    let not_queued = executor.spawn(|_| Ok("never run"));
+    executor.shutdown();
+
+    assert_eq!(block_on(queued).unwrap(), "done");
+    assert!(matches!(block_on(not_queued), Err(Error::ExecutorShutdown)));
+}
+
+#[test]
+fn async_executor_shut_down_from_a_task() {
+    let executor = Arc::new(Mutex::new(Some(
+        AsyncExecutor::new(jvm().clone(), 2).unwrap(),
+    )));
+
+    let task_executor = executor.clone();
+    let future = executor.lock().unwrap().as_ref().unwrap().spawn(move |_| {
+        let executor = task_executor.lock().unwrap().take().unwrap();
+        executor.shutdown();
+        Ok("shut down")
+    });
+    assert_eq!(block_on(future).unwrap(), "shut down");
+}
@@ -0,0 +1,216 @@
+#![cfg(feature = "invocation")]
+
+use std::{task::Poll, thread};
+
+use jni::{
+    errors::Error,
+    objects::{JCompletableFuture, JObject, JString, JValue},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, block_on, jvm, poll_once, unwrap};
+
+fn get_string(env: &JNIEnv, obj: JObject) -> String {
+    unwrap(env, env.get_string(JString::from(obj))).into()
+}
+
+fn is_state<'a>(env: &JNIEnv<'a>, future: JCompletableFuture<'a>, method: &str) -> bool {
+    unwrap(
+        env,
+        env.call_method(future, method, "()Z", &[])
+            .and_then(|v| v.z()),
+    )
+}
+
+#[test]
+fn completable_future_into_future_completed() {
+    let env = attach_current_thread();
+
+    let completable = unwrap(&env, JCompletableFuture::new(&env));
+    let value = unwrap(&env, env.new_string("done"));
+    assert!(unwrap(&env, completable.complete(&env, value)));
+    assert!(unwrap(&env, completable.is_done(&env)));
+
+    // The callback runs synchronously, as the future is already completed.
+    let mut future = unwrap(&env, completable.into_future(&env));
+    let result = match poll_once(&mut future) {
+        Poll::Ready(result) => unwrap(&env, result),
+        Poll::Pending => panic!("the future should be ready"),
+    };
+    assert_eq!(get_string(&env, result.as_obj()), "done");
+}
+
+#[test]
+fn completable_future_into_future_completed_later() {
+    let env = attach_current_thread();
+
+    let completable = unwrap(&env, JCompletableFuture::new(&env));
+    let mut future = unwrap(&env, completable.into_future(&env));
+    assert!(poll_once(&mut future).is_pending());
+
+    let target = unwrap(&env, env.new_global_ref(completable));
+    let completer = thread::spawn(move || {
+        let env = attach_current_thread();
+        let completable = JCompletableFuture::from(target.as_obj());
+        let value = unwrap(&env, env.new_string("later"));
+        unwrap(&env, completable.complete(&env, value));
+    });
+
+    let result = unwrap(&env, block_on(future));
+    assert_eq!(get_string(&env, result.as_obj()), "later");
+    completer.join().unwrap();
+
+    let completable = unwrap(&env, JCompletableFuture::new(&env));
+    let future = unwrap(&env, completable.into_future(&env));
+    unwrap(&env, completable.complete(&env, JObject::null()));
+    assert!(unwrap(&env, block_on(future)).as_obj().is_null());
+}
+
+#[test]
+fn completable_future_into_future_exceptionally() {
+    let env = attach_current_thread();
+
+    let completable = unwrap(&env, JCompletableFuture::new(&env));
+    let future = unwrap(&env, completable.into_future(&env));
+    let completed =
+        completable.complete_exceptionally(&env, ("java/lang/IllegalStateException", "boom"));
+    assert!(unwrap(&env, completed));
+
+    match block_on(future) {
+        Err(Error::CapturedException(e)) => {
+            assert_eq!(e.class, "java.lang.IllegalStateException");
+            assert_eq!(e.message.as_deref(), Some("boom"));
+        }
+        other => panic!("unexpected result: {:?}", other.map(drop)),
+    }
+    assert!(!unwrap(&env, env.exception_check()));
+}
+
+#[test]
+fn completable_future_into_future_unwraps_completion_exception() {
+    let env = attach_current_thread();
+
+    let completable = unwrap(&env, JCompletableFuture::new(&env));
+    // A dependent stage completes with a `CompletionException` wrapping the original one.
+    let function = unwrap(
+        &env,
+        env.call_static_method(
+            "java/util/function/Function",
+            "identity",
+            "()Ljava/util/function/Function;",
+            &[],
+        )
+        .and_then(|v| v.l()),
+    );
+    let dependent = unwrap(
+        &env,
+        env.call_method(
+            completable,
+            "thenApply",
+            "(Ljava/util/function/Function;)Ljava/util/concurrent/CompletableFuture;",
+            &[JValue::from(function)],
+        )
+        .and_then(|v| v.l()),
+    );
+    let future = unwrap(&env, JCompletableFuture::from(dependent).into_future(&env));
+    unwrap(
+        &env,
+        completable.complete_exceptionally(&env, ("java/lang/IllegalStateException", "boom")),
+    );
+
+    match block_on(future) {
+        Err(Error::CapturedException(e)) => {
+            assert_eq!(e.class, "java.lang.IllegalStateException")
+        }
+        other => panic!("unexpected result: {:?}", other.map(drop)),
+    }
+}
+
+#[test]
+fn completable_future_completed_by() {
+    let env = attach_current_thread();
+
+    let value = unwrap(&env, env.new_string("from rust"));
+    let value = unwrap(&env, env.new_global_ref(value));
+    let (completable, completing) = unwrap(
+        &env,
+        JCompletableFuture::completed_by(&env, async { Ok(value) }),
+    );
+    assert!(!unwrap(&env, completable.is_done(&env)));
+
+    unwrap(&env, block_on(completing));
+    let result = unwrap(
+        &env,
+        env.call_method(completable, "join", "()Ljava/lang/Object;", &[])
+            .and_then(|v| v.l()),
+    );
+    assert_eq!(get_string(&env, result), "from rust");
+}
+
+#[test]
+fn completable_future_completed_by_error() {
+    let env = attach_current_thread();
+
+    let (completable, completing) = unwrap(
+        &env,
+        JCompletableFuture::completed_by(&env, async { Err(Error::NoJavaVM) }),
+    );
+    unwrap(&env, block_on(completing));
+    assert!(is_state(&env, completable, "isCompletedExceptionally"));
+    assert!(!is_state(&env, completable, "isCancelled"));
+}
+
+#[test]
+fn completable_future_completed_by_dropped() {
+    let env = attach_current_thread();
+
+    let (completable, completing) = unwrap(
+        &env,
+        JCompletableFuture::completed_by(&env, std::future::pending()),
+    );
+    let mut completing = Box::pin(completing);
+    assert!(poll_once(&mut completing).is_pending());
+    drop(completing);
+    assert!(is_state(&env, completable, "isCancelled"));
+}
+
+#[test]
+fn completable_future_native_completion_coexists_with_other_copies() {
+    let env = attach_current_thread();
+
+    // Another copy of the crate defining the callback class without a class loader, as it used
+    // to, does not get in the way.
+    let bytes = include_bytes!("../src/wrapper/objects/java/jni_rs/NativeCompletion.class");
+    let other = unwrap(
+        &env,
+        env.define_class("jni_rs/NativeCompletion", JObject::null(), bytes),
+    );
+    unwrap(&env, env.delete_local_ref(other.into()));
+
+    let completable = unwrap(&env, JCompletableFuture::new(&env));
+    let future = unwrap(&env, completable.into_future(&env));
+    let value = unwrap(&env, env.new_string("private"));
+    unwrap(&env, completable.complete(&env, value));
+    let result = unwrap(&env, block_on(future));
+    assert_eq!(get_string(&env, result.as_obj()), "private");
+}
+
+#[test]
+fn completable_future_native_completion_is_shared() {
+    // The callback class is defined once, from any thread.
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                let env = jvm().attach_current_thread().unwrap();
+                let completable = unwrap(&env, JCompletableFuture::new(&env));
+                let future = unwrap(&env, completable.into_future(&env));
+                unwrap(&env, completable.complete(&env, JObject::null()));
+                assert!(unwrap(&env, block_on(future)).as_obj().is_null());
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
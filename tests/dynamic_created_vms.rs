@@ -0,0 +1,38 @@
+#![cfg(all(feature = "dynamic-invocation", unix))]
+
+use std::{os::raw::c_void, ptr};
+
+use jni::{
+    sys::{self, jint, JavaVMInitArgs, JNI_OK, JNI_VERSION_1_8},
+    JavaVM, JvmLibrary,
+};
+use libloading::os::unix::{Library, RTLD_GLOBAL, RTLD_NOW};
+
+type CreateJavaVM =
+    unsafe extern "system" fn(*mut *mut sys::JavaVM, *mut *mut c_void, *mut c_void) -> jint;
+
+// Launches the VM like a host application would, without going through `JvmLibrary`, so this
+// must stay the only test of its binary.
+#[test]
+pub fn created_vms_uses_already_loaded_library() {
+    let path = JvmLibrary::locate().unwrap();
+    let library = unsafe { Library::open(Some(&path), RTLD_NOW | RTLD_GLOBAL) }.unwrap();
+    let create_java_vm = unsafe { library.get::<CreateJavaVM>(b"JNI_CreateJavaVM\0") }.unwrap();
+
+    let mut args = JavaVMInitArgs {
+        version: JNI_VERSION_1_8,
+        nOptions: 0,
+        options: ptr::null_mut(),
+        ignoreUnrecognized: 0,
+    };
+    let mut vm: *mut sys::JavaVM = ptr::null_mut();
+    let mut env: *mut c_void = ptr::null_mut();
+    let res = unsafe { create_java_vm(&mut vm, &mut env, &mut args as *mut _ as *mut c_void) };
+    assert_eq!(res, JNI_OK);
+    unsafe { (**vm).DetachCurrentThread.unwrap()(vm) };
+
+    let vms = JavaVM::created_vms().unwrap();
+    assert_eq!(vms.len(), 1);
+    assert_eq!(vms[0].get_java_vm_pointer(), vm);
+    assert!(JvmLibrary::installed().is_none());
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "dynamic-invocation")]
+
+use jni::{JavaVM, JvmError, JvmLibrary};
+
+mod util;
+use util::{attach_current_thread, call_java_abs};
+
+#[test]
+pub fn locate_finds_libjvm() {
+    let path = JvmLibrary::locate().unwrap();
+    assert!(path.is_file());
+
+    let library = unsafe { JvmLibrary::load(&path) }.unwrap();
+    assert_eq!(library.path(), path);
+}
+
+#[test]
+pub fn load_reports_missing_library() {
+    let res = unsafe { JvmLibrary::load("/nonexistent/libjvm.so") };
+    assert!(matches!(res, Err(JvmError::LibraryLoad { .. })));
+}
+
+#[test]
+pub fn java_vm_uses_loaded_library() {
+    let env = attach_current_thread();
+    assert_eq!(call_java_abs(&env, -3), 3);
+
+    let installed = JvmLibrary::installed().unwrap();
+    assert_eq!(installed.path(), JvmLibrary::locate().unwrap());
+}
+
+#[test]
+pub fn existing_uses_loaded_library() {
+    let env = attach_current_thread();
+    let vm = JavaVM::existing().unwrap();
+    assert_eq!(
+        vm.get_java_vm_pointer(),
+        env.get_java_vm().unwrap().get_java_vm_pointer()
+    );
+}
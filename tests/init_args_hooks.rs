@@ -0,0 +1,37 @@
+#![cfg(feature = "invocation")]
+
+use std::sync::{Arc, Mutex};
+
+use jni::{InitArgsBuilder, JNIVersion, JavaVM};
+
+#[test]
+#[cfg(any(
+    all(target_arch = "x86_64", not(target_os = "windows")),
+    all(target_arch = "aarch64", target_os = "linux")
+))]
+pub fn vfprintf_hook_receives_vm_output() {
+    let output = Arc::new(Mutex::new(String::new()));
+    let hook_output = output.clone();
+
+    let jvm_args = InitArgsBuilder::new()
+        .version(JNIVersion::V8)
+        .option("-Xcheck:jni")
+        .option("-Xlog:gc")
+        .vfprintf_hook(move |message| hook_output.lock().unwrap().push_str(message))
+        .exit_hook(|code| panic!("unexpected exit with status {}", code))
+        .abort_hook(|| panic!("unexpected abort"))
+        .build()
+        .unwrap();
+    let jvm = JavaVM::new(jvm_args).unwrap();
+
+    let env = jvm.attach_current_thread().unwrap();
+    env.call_static_method("java/lang/System", "gc", "()V", &[])
+        .unwrap();
+
+    let output = output.lock().unwrap();
+    assert!(
+        output.contains("Pause Full (System.gc())"),
+        "unexpected VM output: {:?}",
+        output
+    );
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "invocation")]
+
+use std::{env, fs};
+
+use jni::{
+    errors::{Error, JniError},
+    objects::JString,
+    InitArgs, InitArgsBuilder, JNIEnv, JNIVersion, JavaVM, JvmError,
+};
+
+fn get_property(env: &JNIEnv, key: &str) -> String {
+    let key = env.new_string(key).unwrap();
+    let value = env
+        .call_static_method(
+            "java/lang/System",
+            "getProperty",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &[key.into()],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    env.get_string(JString::from(value)).unwrap().into()
+}
+
+#[test]
+pub fn options_reach_the_vm() {
+    let dir = env::temp_dir().join(format!("jni-rs-argfiles-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let argfile = dir.join("vm.args");
+    fs::write(
+        &argfile,
+        "# Options shared with `java @vm.args`\n\
+         -Djni.test.argfile=\"from the file\" \\\n\
+         \t-Djni.test.continued=yes\n",
+    )
+    .unwrap();
+    let env_argfile = dir.join("env.args");
+    fs::write(&env_argfile, "-Djni.test.env.argfile=expanded").unwrap();
+    env::set_var(
+        "JNI_TEST_JAVA_OPTIONS",
+        format!("-Djni.test.env='a b' @{}", env_argfile.display()),
+    );
+
+    let jvm_args = InitArgsBuilder::new()
+        .version(JNIVersion::V8)
+        .option("-Xcheck:jni")
+        .classpath(&["first.jar", "second"])
+        .system_property("jni.test.property", "a value=with spaces")
+        .max_heap(64 << 20)
+        .add_opens("java.base", "java.lang", "ALL-UNNAMED")
+        .options_from_file(&argfile)
+        .options_from_env("JNI_TEST_JAVA_OPTIONS")
+        .options_from_env("JNI_TEST_UNSET_JAVA_OPTIONS")
+        .build()
+        .unwrap();
+    let jvm = JavaVM::new(jvm_args).unwrap();
+    let env = jvm.attach_current_thread().unwrap();
+
+    let separator = if cfg!(windows) { ";" } else { ":" };
+    assert_eq!(
+        get_property(&env, "java.class.path"),
+        format!("first.jar{}second", separator)
+    );
+    assert_eq!(
+        get_property(&env, "jni.test.property"),
+        "a value=with spaces"
+    );
+
+    assert_eq!(get_property(&env, "jni.test.argfile"), "from the file");
+    assert_eq!(get_property(&env, "jni.test.continued"), "yes");
+    assert_eq!(get_property(&env, "jni.test.env"), "a b");
+    assert_eq!(get_property(&env, "jni.test.env.argfile"), "expanded");
+    fs::remove_dir_all(&dir).unwrap();
+
+    let runtime = env
+        .call_static_method(
+            "java/lang/Runtime",
+            "getRuntime",
+            "()Ljava/lang/Runtime;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    let max_memory = env
+        .call_method(runtime, "maxMemory", "()J", &[])
+        .unwrap()
+        .j()
+        .unwrap();
+    assert!(max_memory <= 64 << 20);
+}
+
+#[test]
+pub fn defaults_check_the_jni_version() {
+    let args = InitArgs::defaults(JNIVersion::V8).unwrap();
+    assert_eq!(args.version(), JNIVersion::V8);
+
+    assert!(matches!(
+        InitArgs::defaults(JNIVersion::V1),
+        Err(JvmError::JniCall(JniError::WrongVersion))
+    ));
+    assert!(matches!(
+        InitArgs::defaults(JNIVersion::Invalid(0x7fff_0000)),
+        Err(JvmError::JniCall(JniError::WrongVersion))
+    ));
+
+    let res = InitArgsBuilder::new().version(JNIVersion::V1).build();
+    assert!(matches!(
+        res,
+        Err(JvmError::JniCall(JniError::WrongVersion))
+    ));
+    assert!(matches!(
+        Error::from(res.err().unwrap()),
+        Error::JniCall(JniError::WrongVersion)
+    ));
+
+    let args = InitArgsBuilder::new()
+        .version(JNIVersion::Invalid(0x7fff_0000))
+        .build()
+        .unwrap();
+    assert!(matches!(
+        JavaVM::new(args),
+        Err(Error::JniCall(JniError::WrongVersion))
+    ));
+}
@@ -0,0 +1,128 @@
+#![cfg(all(feature = "invocation", feature = "macros"))]
+
+use jni::{
+    descriptors::Desc,
+    errors::Error,
+    java_class,
+    objects::{JClass, JObject},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+java_class! {
+    /// `java.util.ArrayList`, with a handful of methods.
+    pub class java.util.ArrayList as JArrayList {
+        new();
+        new(int) as with_capacity;
+        fn add(Object) -> boolean;
+        fn add(int, Object) as insert;
+        fn get(int) -> Object;
+        fn size() -> int;
+        fn isEmpty() -> boolean;
+        fn clear();
+    }
+
+    class java.lang.Integer {
+        static fn valueOf(int) -> Integer;
+        static fn toHexString(int) -> String;
+        fn intValue() -> int;
+    }
+
+    class java.lang.StringBuilder {
+        new(String);
+        fn append(char) -> StringBuilder;
+        fn reverse() -> StringBuilder;
+        fn toString() -> String;
+    }
+
+    class Unknown {
+        fn copy() -> Unknown;
+        fn name() -> String;
+    }
+}
+
+#[test]
+pub fn java_class_calls_methods() {
+    let env = attach_current_thread();
+
+    let list = unwrap(&env, JArrayList::with_capacity(&env, 10));
+    assert!(unwrap(&env, list.is_empty(&env)));
+
+    let one = unwrap(&env, Integer::value_of(&env, 1));
+    let two = unwrap(&env, Integer::value_of(&env, 2));
+    assert!(unwrap(&env, list.add(&env, two)));
+    unwrap(&env, list.insert(&env, 0, one));
+    assert_eq!(unwrap(&env, list.size(&env)), 2);
+
+    let first = Integer::from(unwrap(&env, list.get(&env, 0)));
+    assert_eq!(unwrap(&env, first.int_value(&env)), 1);
+    let second = Integer::from(unwrap(&env, list.get(&env, 1)));
+    assert_eq!(unwrap(&env, second.int_value(&env)), 2);
+
+    unwrap(&env, list.clear(&env));
+    assert_eq!(unwrap(&env, list.size(&env)), 0);
+}
+
+#[test]
+pub fn java_class_converts_return_types() {
+    let env = attach_current_thread();
+
+    let hex = unwrap(&env, Integer::to_hex_string(&env, 255));
+    let hex: String = unwrap(&env, env.get_string(hex)).into();
+    assert_eq!(hex, "ff");
+
+    let input = unwrap(&env, env.new_string("abc"));
+    let builder = unwrap(&env, StringBuilder::new(&env, input));
+    let builder = unwrap(&env, builder.append(&env, 'd' as u16));
+    let reversed = unwrap(&env, builder.reverse(&env));
+    let output = unwrap(&env, reversed.to_string(&env));
+    let output: String = unwrap(&env, env.get_string(output)).into();
+    assert_eq!(output, "dcba");
+}
+
+#[test]
+pub fn java_class_is_an_object() {
+    let env = attach_current_thread();
+
+    let list = unwrap(&env, JArrayList::new(&env));
+    let obj: JObject = list.into();
+    assert!(unwrap(&env, env.is_instance_of(obj, "java/util/List")));
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(*list, "java/util/ArrayList")
+    ));
+
+    let class: JClass = unwrap(&env, list.lookup(&env));
+    let expected = unwrap(&env, env.find_class(JArrayList::CLASS_NAME));
+    assert!(unwrap(&env, env.is_same_object(class, expected)));
+
+    let class = unwrap(&env, Integer::class(&env));
+    let expected = unwrap(&env, env.find_class("java/lang/Integer"));
+    assert!(unwrap(&env, env.is_same_object(class, expected)));
+}
+
+#[test]
+pub fn java_class_reports_exceptions() {
+    let env = attach_current_thread();
+
+    let list = unwrap(&env, JArrayList::new(&env));
+    let res = list.get(&env, 3);
+    assert!(matches!(res, Err(Error::JavaException)));
+    let exception = unwrap(&env, env.take_exception()).unwrap();
+    assert_eq!(exception.class, "java.lang.IndexOutOfBoundsException");
+}
+
+#[test]
+pub fn java_class_unqualified_name_is_in_default_package() {
+    let env = attach_current_thread();
+
+    assert_eq!(Unknown::CLASS_NAME, "Unknown");
+    // The simple name of the declared class stands for it in the member types.
+    let _: fn(&Unknown<'static>, &JNIEnv<'static>) -> jni::errors::Result<Unknown<'static>> =
+        Unknown::copy;
+    assert!(Unknown::class(&env).is_err());
+    let exception = unwrap(&env, env.take_exception()).unwrap();
+    assert_eq!(exception.class, "java.lang.NoClassDefFoundError");
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "invocation")]
+
+use jni::{errors::Error, InitArgsBuilder, JNIVersion, JavaVM};
+
+#[test]
+pub fn destroy_java_vm() {
+    let jvm_args = InitArgsBuilder::new()
+        .version(JNIVersion::V8)
+        .option("-Xcheck:jni")
+        .build()
+        .unwrap();
+    let jvm = JavaVM::new(jvm_args).unwrap();
+
+    let global = {
+        let env = jvm.attach_current_thread().unwrap();
+        let string = env.new_string("destroyed").unwrap();
+        env.new_global_ref(string).unwrap()
+    };
+    assert_eq!(jvm.threads_attached(), 0);
+
+    let same_vm = unsafe { JavaVM::from_raw(jvm.get_java_vm_pointer()) }.unwrap();
+    assert!(!same_vm.is_destroyed());
+    jvm.destroy().unwrap();
+
+    assert!(same_vm.is_destroyed());
+    assert_eq!(same_vm.threads_attached(), 0);
+    assert!(matches!(same_vm.get_env(), Err(Error::JavaVMDestroyed)));
+    assert!(matches!(
+        same_vm.attach_current_thread(),
+        Err(Error::JavaVMDestroyed)
+    ));
+    assert!(matches!(
+        same_vm.attach_current_thread_as_daemon(),
+        Err(Error::JavaVMDestroyed)
+    ));
+
+    // The reference went away with the VM.
+    drop(global);
+}
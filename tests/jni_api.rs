@@ -6,11 +6,13 @@ use std::str::FromStr;
 use jni::{
     descriptors::Desc,
     errors::Error,
-    objects::{AutoLocal, JByteBuffer, JList, JObject, JString, JThrowable, JValue},
+    objects::{
+        AutoLocal, JByteBuffer, JList, JObject, JObjectRefType, JString, JThrowable, JValue,
+    },
     signature::JavaType,
     strings::JNIString,
-    sys::{jint, jobject, jsize},
-    JNIEnv,
+    sys::{jdouble, jint, jobject, jsize},
+    JNIEnv, JNIVersion, JavaVM,
 };
 
 mod util;
@@ -298,6 +300,208 @@ pub fn call_static_method_wrong_arg() {
     assert_pending_java_exception(&env);
 }
 
+#[test]
+pub fn call_nonvirtual_method_calls_super() {
+    let env = attach_current_thread();
+
+    let list = env.auto_local(unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[])));
+
+    let virtual_str = unwrap(
+        &env,
+        env.call_method(&list, "toString", "()Ljava/lang/String;", &[]),
+    );
+    let virtual_str: String =
+        unwrap(&env, env.get_string(unwrap(&env, virtual_str.l()).into())).into();
+    assert_eq!(virtual_str, "[]");
+
+    let super_str = unwrap(
+        &env,
+        env.call_nonvirtual_method(
+            &list,
+            "java/lang/Object",
+            "toString",
+            "()Ljava/lang/String;",
+            &[],
+        ),
+    );
+    let super_str: String = unwrap(&env, env.get_string(unwrap(&env, super_str.l()).into())).into();
+    assert!(super_str.starts_with("java.util.ArrayList@"));
+}
+
+#[test]
+pub fn call_nonvirtual_method_unchecked_ok() {
+    let env = attach_current_thread();
+
+    let list = env.auto_local(unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[])));
+    let class = env.auto_local(unwrap(&env, env.find_class("java/util/AbstractCollection")));
+    let method_id = unwrap(&env, env.get_method_id(&class, "isEmpty", "()Z"));
+
+    let is_empty = unwrap(
+        &env,
+        env.call_nonvirtual_method_unchecked(
+            &list,
+            &class,
+            method_id,
+            JavaType::from_str("Z").unwrap(),
+            &[],
+        ),
+    );
+    assert!(unwrap(&env, is_empty.z()));
+}
+
+#[test]
+pub fn call_nonvirtual_method_wrong_arg_count() {
+    let env = attach_current_thread();
+
+    let list = env.auto_local(unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[])));
+    let res = env.call_nonvirtual_method(
+        &list,
+        "java/lang/Object",
+        "toString",
+        "()Ljava/lang/String;",
+        &[JValue::from(1)],
+    );
+    assert!(matches!(res, Err(Error::InvalidArgList(_))));
+}
+
+#[test]
+pub fn call_nonvirtual_method_not_instance_of_class() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string("abc"));
+    let res = env.call_nonvirtual_method(
+        string,
+        "java/util/AbstractCollection",
+        "isEmpty",
+        "()Z",
+        &[],
+    );
+    assert!(matches!(res, Err(Error::NotInstanceOf(_))));
+    assert!(!unwrap(&env, env.exception_check()));
+}
+
+#[test]
+pub fn capture_exception_from_call() {
+    let env = attach_current_thread();
+
+    let x = JValue::Long(4_000_000_000);
+    let res = env.capture_exception(env.call_static_method(
+        MATH_CLASS,
+        MATH_TO_INT_METHOD_NAME,
+        MATH_TO_INT_SIGNATURE,
+        &[x],
+    ));
+
+    match res {
+        Err(Error::CapturedException(e)) => {
+            assert_eq!(e.class, "java.lang.ArithmeticException");
+            assert_eq!(e.message.as_deref(), Some("integer overflow"));
+            assert!(e.stack_trace[0].contains("java.lang.Math.toIntExact"));
+            assert!(e.cause.is_none());
+        }
+        other => panic!("CapturedException expected, got {:?}", other),
+    }
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn take_exception_with_cause() {
+    let env = attach_current_thread();
+
+    assert!(unwrap(&env, env.take_exception()).is_none());
+
+    let msg = env.new_string("inner").unwrap();
+    let cause = env
+        .new_object(
+            "java/lang/IllegalStateException",
+            "(Ljava/lang/String;)V",
+            &[JValue::from(msg)],
+        )
+        .unwrap();
+    let msg = env.new_string("outer").unwrap();
+    let exception = env
+        .new_object(
+            EXCEPTION_CLASS,
+            "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+            &[JValue::from(msg), JValue::from(cause)],
+        )
+        .unwrap();
+    env.throw(JThrowable::from(exception)).unwrap();
+
+    let captured = unwrap(&env, env.take_exception()).expect("exception should be pending");
+    assert!(!env.exception_check().unwrap());
+    assert_eq!(captured.to_string(), "java.lang.Exception: outer");
+
+    let source = std::error::Error::source(&captured).expect("cause expected");
+    assert_eq!(source.to_string(), "java.lang.IllegalStateException: inner");
+    assert!(source.source().is_none());
+}
+
+#[test]
+pub fn throw_on_failure_returns_value() {
+    let env = attach_current_thread();
+
+    let res = env.throw_on_failure(0, |_| Ok::<jint, Error>(42));
+
+    assert_eq!(res, 42);
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn throw_on_failure_throws_error() {
+    let env = attach_current_thread();
+
+    let res = env.throw_on_failure(-1, |_| -> Result<jint, Error> {
+        Err(Error::NullPtr("test"))
+    });
+
+    assert_eq!(res, -1);
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("Null pointer in test"),
+    );
+}
+
+#[test]
+pub fn throw_on_failure_keeps_pending_exception() {
+    let env = attach_current_thread();
+
+    let x = JValue::Long(4_000_000_000);
+    let res = env.throw_on_failure(-1, |env| {
+        env.call_static_method(
+            MATH_CLASS,
+            MATH_TO_INT_METHOD_NAME,
+            MATH_TO_INT_SIGNATURE,
+            &[x],
+        )?
+        .i()
+    });
+
+    assert_eq!(res, -1);
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(ARITHMETIC_EXCEPTION_CLASS),
+        Some("integer overflow"),
+    );
+}
+
+#[test]
+pub fn throw_on_failure_catches_panic() {
+    let env = attach_current_thread();
+
+    let res = env.throw_on_failure(JObject::null(), |_| -> Result<JObject, Error> {
+        panic!("panic in native method")
+    });
+
+    assert!(res.is_null());
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("panic in native method"),
+    );
+}
+
 #[test]
 pub fn java_byte_array_from_slice() {
     let env = attach_current_thread();
@@ -410,6 +614,54 @@ pub fn get_byte_array_elements_auto() {
     assert_eq!(res[2], 4);
 }
 
+#[test]
+pub fn get_array_elements_int() {
+    let env = attach_current_thread();
+
+    let java_array = unwrap(&env, env.new_int_array(3));
+    unwrap(&env, env.set_int_array_region(java_array, 0, &[1, 2, 3]));
+
+    // Use a scope to test Drop
+    {
+        // Nothing else accesses the array while `elements` is alive.
+        let mut elements = unwrap(&env, unsafe {
+            env.get_array_elements(java_array, ReleaseMode::CopyBack)
+        });
+
+        // Check slice access
+        assert_eq!(&elements[..], &[1, 2, 3]);
+
+        // Modify
+        for element in elements.iter_mut() {
+            *element += 1;
+        }
+    }
+
+    // Confirm modification of original Java array
+    let mut res: [jint; 3] = [0; 3];
+    unwrap(&env, env.get_int_array_region(java_array, 0, &mut res));
+    assert_eq!(res, [2, 3, 4]);
+}
+
+#[test]
+pub fn get_array_elements_double_commit() {
+    let env = attach_current_thread();
+
+    let java_array = unwrap(&env, env.new_double_array(2));
+
+    let mut elements = unwrap(&env, unsafe {
+        env.get_array_elements(java_array, ReleaseMode::NoCopyBack)
+    });
+    assert_eq!(elements.len(), 2);
+    elements[0] = 1.5;
+    elements[1] = -2.5;
+    elements.commit();
+
+    let mut res: [jdouble; 2] = [0.0; 2];
+    unwrap(&env, env.get_double_array_region(java_array, 0, &mut res));
+    assert_eq!(res, [1.5, -2.5]);
+}
+
 #[test]
 pub fn java_get_primitive_array_critical() {
     let env = attach_current_thread();
@@ -459,7 +711,7 @@ pub fn java_get_primitive_array_critical() {
 
 #[test]
 pub fn get_primitive_array_critical_auto() {
-    let env = attach_current_thread();
+    let mut env = attach_current_thread();
 
     // Create original Java array
     let buf: &[u8] = &[1, 2, 3];
@@ -470,31 +722,17 @@ pub fn get_primitive_array_critical_auto() {
     // Use a scope to test Drop
     {
         // Get primitive array elements auto wrapper
-        let auto_ptr = env
+        let mut auto_array = env
             .get_auto_primitive_array_critical(java_array, ReleaseMode::CopyBack)
             .unwrap();
 
-        // Get pointer
-        let ptr = auto_ptr.as_ptr();
-
-        // Convert void pointer to an unsigned byte array, without copy
-        let mut vec;
-        unsafe { vec = Vec::from_raw_parts(ptr as *mut u8, 3, 3) }
-
         // Check
-        assert_eq!(vec[0], 1);
-        assert_eq!(vec[1], 2);
-        assert_eq!(vec[2], 3);
+        assert_eq!(*auto_array, [1, 2, 3]);
 
         // Modify
-        vec[0] += 1;
-        vec[1] += 1;
-        vec[2] += 1;
-
-        // Release
-        // Make sure vec's destructor doesn't free the data it thinks it owns when it goes out
-        // of scope (avoid double free)
-        std::mem::forget(vec);
+        for elem in auto_array.iter_mut() {
+            *elem += 1;
+        }
     }
 
     // Confirm modification of original Java array
@@ -505,6 +743,208 @@ pub fn get_primitive_array_critical_auto() {
     assert_eq!(res[2], 4);
 }
 
+#[test]
+pub fn reflected_method_round_trip() {
+    let env = attach_current_thread();
+
+    let method_id = unwrap(&env, env.get_method_id(STRING_CLASS, "length", "()I"));
+    let method = unwrap(&env, env.to_reflected_method(STRING_CLASS, method_id));
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(*method, "java/lang/reflect/Method")
+    ));
+    let name = unwrap(
+        &env,
+        env.call_method(*method, "getName", "()Ljava/lang/String;", &[]),
+    );
+    let name: String = unwrap(&env, env.get_string(unwrap(&env, name.l()).into())).into();
+    assert_eq!(name, "length");
+
+    let id = unwrap(&env, env.from_reflected_method(method));
+    let s = unwrap(&env, env.new_string("hello"));
+    let len = unwrap(
+        &env,
+        env.call_method_unchecked(s, id, JavaType::from_str("I").unwrap(), &[]),
+    );
+    assert_eq!(unwrap(&env, len.i()), 5);
+}
+
+#[test]
+pub fn reflected_static_method_round_trip() {
+    let env = attach_current_thread();
+
+    let method_id = unwrap(
+        &env,
+        env.get_static_method_id(MATH_CLASS, MATH_ABS_METHOD_NAME, MATH_ABS_SIGNATURE),
+    );
+    let method = unwrap(&env, env.to_reflected_static_method(MATH_CLASS, method_id));
+    let id = unwrap(&env, env.from_reflected_static_method(method));
+    let res = unwrap(
+        &env,
+        env.call_static_method_unchecked(
+            MATH_CLASS,
+            id,
+            JavaType::from_str("I").unwrap(),
+            &[JValue::from(-3)],
+        ),
+    );
+    assert_eq!(unwrap(&env, res.i()), 3);
+}
+
+#[test]
+pub fn reflected_static_field_round_trip() {
+    let env = attach_current_thread();
+
+    let field_id = unwrap(
+        &env,
+        env.get_static_field_id(INTEGER_CLASS, "MAX_VALUE", "I"),
+    );
+    let field = unwrap(&env, env.to_reflected_static_field(INTEGER_CLASS, field_id));
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(*field, "java/lang/reflect/Field")
+    ));
+    let name = unwrap(
+        &env,
+        env.call_method(*field, "getName", "()Ljava/lang/String;", &[]),
+    );
+    let name: String = unwrap(&env, env.get_string(unwrap(&env, name.l()).into())).into();
+    assert_eq!(name, "MAX_VALUE");
+
+    let id = unwrap(&env, env.from_reflected_static_field(field));
+    let value = unwrap(
+        &env,
+        env.get_static_field_unchecked(INTEGER_CLASS, id, JavaType::from_str("I").unwrap()),
+    );
+    assert_eq!(unwrap(&env, value.i()), i32::MAX);
+}
+
+#[test]
+pub fn from_reflected_static_rejects_instance_members() {
+    let env = attach_current_thread();
+
+    let method_id = unwrap(&env, env.get_method_id(INTEGER_CLASS, "intValue", "()I"));
+    let method = unwrap(&env, env.to_reflected_method(INTEGER_CLASS, method_id));
+    let res = env.from_reflected_static_method(method);
+    assert!(matches!(res, Err(Error::NotStatic(_))));
+
+    let field_id = unwrap(&env, env.get_field_id(INTEGER_CLASS, "value", "I"));
+    let field = unwrap(&env, env.to_reflected_field(INTEGER_CLASS, field_id));
+    let res = env.from_reflected_static_field(field);
+    assert!(matches!(res, Err(Error::NotStatic(_))));
+}
+
+#[test]
+pub fn get_object_ref_type() {
+    let env = attach_current_thread();
+
+    let local = unwrap(&env, env.new_string("ref type"));
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(local)),
+        JObjectRefType::Local
+    );
+
+    let global = unwrap(&env, env.new_global_ref(local));
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(global.as_obj())),
+        JObjectRefType::Global
+    );
+
+    let weak = unwrap(&env, env.new_weak_ref(local)).unwrap();
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(JObject::from(weak.as_raw()))),
+        JObjectRefType::WeakGlobal
+    );
+
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(JObject::null())),
+        JObjectRefType::Invalid
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "deleting a Global reference as a Local one")]
+pub fn auto_local_of_global_ref_panics() {
+    let env = attach_current_thread();
+
+    let global = unwrap(
+        &env,
+        env.new_global_ref(unwrap(&env, env.new_string("global"))),
+    );
+    let _local = AutoLocal::new(&env, global.as_obj());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "deleting a Global reference as a Local one")]
+pub fn delete_local_ref_of_global_ref_panics() {
+    let env = attach_current_thread();
+
+    let global = unwrap(
+        &env,
+        env.new_global_ref(unwrap(&env, env.new_string("global"))),
+    );
+    let _ = env.delete_local_ref(global.as_obj());
+}
+
+#[test]
+pub fn get_version_supports_modules() {
+    let env = attach_current_thread();
+
+    let version = unwrap(&env, env.get_version());
+    assert!(version.is_at_least(JNIVersion::V9));
+    assert_eq!(
+        JNIVersion::from(i32::from(JNIVersion::V21)),
+        JNIVersion::V21
+    );
+}
+
+#[test]
+pub fn get_module() {
+    let env = attach_current_thread();
+
+    let module = unwrap(&env, env.get_module(STRING_CLASS));
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(*module, "java/lang/Module")
+    ));
+    let name = unwrap(
+        &env,
+        env.call_method(*module, "getName", "()Ljava/lang/String;", &[]),
+    );
+    let name: String = unwrap(&env, env.get_string(unwrap(&env, name.l()).into())).into();
+    assert_eq!(name, "java.base");
+}
+
+#[test]
+pub fn is_virtual_thread() {
+    let env = attach_current_thread();
+
+    let thread = unwrap(
+        &env,
+        env.call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        ),
+    );
+    let thread = unwrap(&env, thread.l());
+    let res = env.is_virtual_thread(thread);
+    if unwrap(&env, env.get_version()).is_at_least(JNIVersion::V21) {
+        assert!(!unwrap(&env, res));
+    } else {
+        assert!(matches!(
+            res,
+            Err(Error::UnsupportedVersion {
+                required: JNIVersion::V21,
+                ..
+            })
+        ));
+    }
+}
+
 #[test]
 pub fn get_object_class() {
     let env = attach_current_thread();
@@ -525,6 +965,76 @@ pub fn get_object_class_null_arg() {
     assert!(result, "ErrorKind::NullPtr expected as error");
 }
 
+#[test]
+pub fn string_utf16_round_trip() {
+    let env = attach_current_thread();
+
+    // A non-BMP character takes a surrogate pair in UTF-16 and six bytes in modified UTF-8.
+    let text = "h\u{e9}llo \u{1F600}";
+    let utf16: Vec<u16> = text.encode_utf16().collect();
+    let s = unwrap(&env, env.new_string_utf16(&utf16));
+
+    assert_eq!(unwrap(&env, env.get_string_length(s)), 8);
+    assert_eq!(unwrap(&env, env.get_string_utf_length(s)), 13);
+
+    let chars = unwrap(&env, env.get_string_utf16(s));
+    assert_eq!(&*chars, &utf16[..]);
+    assert_eq!(chars.try_to_string().unwrap(), text);
+    assert_eq!(String::from(chars), text);
+
+    let from_utf8: String = unwrap(&env, env.get_string(s)).into();
+    assert_eq!(from_utf8, text);
+}
+
+#[test]
+pub fn string_utf16_unpaired_surrogate() {
+    let env = attach_current_thread();
+
+    let s = unwrap(&env, env.new_string_utf16(&[0x61, 0xD800, 0x62]));
+    let chars = unwrap(&env, env.get_string_utf16(s));
+    assert!(chars.try_to_string().is_err());
+    assert_eq!(String::from(chars), "a\u{FFFD}b");
+}
+
+#[test]
+pub fn get_auto_string_critical() {
+    let mut env = attach_current_thread();
+
+    let text = "critical \u{1F600}";
+    let s = unwrap(&env, env.new_string(text));
+    let utf16: Vec<u16> = text.encode_utf16().collect();
+    {
+        let chars = env.get_auto_string_critical(s).unwrap();
+        assert_eq!(&*chars, &utf16[..]);
+        assert_eq!(chars.try_to_string().unwrap(), text);
+    }
+
+    // The environment can be used again once the guard is dropped.
+    assert_eq!(unwrap(&env, env.get_string_length(s)), utf16.len() as i32);
+}
+
+#[test]
+pub fn get_string_region_ok() {
+    let env = attach_current_thread();
+
+    let s = unwrap(&env, env.new_string("hello world"));
+    let mut buf = [0u16; 5];
+    unwrap(&env, env.get_string_region(s, 6, &mut buf));
+    assert_eq!(String::from_utf16(&buf).unwrap(), "world");
+}
+
+#[test]
+pub fn get_string_region_out_of_bounds() {
+    let env = attach_current_thread();
+
+    let s = unwrap(&env, env.new_string("hello"));
+    let mut buf = [0u16; 5];
+    let res = env.get_string_region(s, 3, &mut buf);
+    assert!(matches!(res, Err(Error::JavaException)));
+    let exception = unwrap(&env, env.take_exception()).unwrap();
+    assert_eq!(exception.class, "java.lang.StringIndexOutOfBoundsException");
+}
+
 #[test]
 pub fn new_direct_byte_buffer() {
     let env = attach_current_thread();
@@ -660,6 +1170,27 @@ pub fn new_primitive_array_wrong() {
     assert_pending_java_exception(&env);
 }
 
+#[test]
+pub fn int_array_region_round_trip() {
+    let env = attach_current_thread();
+
+    let java_array = unwrap(&env, env.new_int_array(4));
+    assert_eq!(unwrap(&env, env.get_array_length(java_array)), 4);
+
+    unwrap(&env, env.set_int_array_region(java_array, 1, &[1, 2, 3]));
+
+    let mut res: [jint; 4] = [-1; 4];
+    unwrap(&env, env.get_int_array_region(java_array, 0, &mut res));
+    assert_eq!(res, [0, 1, 2, 3]);
+
+    let result = env.set_int_array_region(java_array, 2, &[1, 2, 3]);
+    assert_exception(
+        &result,
+        "JNIEnv#set_int_array_region should throw exception",
+    );
+    assert_pending_java_exception(&env);
+}
+
 #[test]
 fn get_super_class_ok() {
     let env = attach_current_thread();
@@ -833,7 +1364,7 @@ where
 }
 
 // Helper method that asserts that result is Error and the cause is JavaException.
-fn assert_exception(res: &Result<jobject, Error>, expect_message: &str) {
+fn assert_exception<T: std::fmt::Debug>(res: &Result<T, Error>, expect_message: &str) {
     assert!(res.is_err());
     assert!(res
         .as_ref()
@@ -882,3 +1413,24 @@ fn assert_exception_message(env: &JNIEnv, exception: JThrowable, expected_messag
     let msg_rust: String = env.get_string(message.into()).unwrap().into();
     assert_eq!(msg_rust, expected_message);
 }
+
+#[test]
+fn java_vm_existing() {
+    let env = attach_current_thread();
+    let expected = unwrap(&env, env.get_java_vm()).get_java_vm_pointer();
+
+    let vms = unwrap(&env, JavaVM::created_vms());
+    assert_eq!(vms.len(), 1);
+    assert_eq!(vms[0].get_java_vm_pointer(), expected);
+
+    let vm = unwrap(&env, JavaVM::existing());
+    assert_eq!(vm.get_java_vm_pointer(), expected);
+    let env = unwrap(&env, vm.attach_current_thread());
+    let abs: jint = unwrap(
+        &env,
+        env.call_static_method("java/lang/Math", "abs", "(I)I", &[JValue::from(-2)]),
+    )
+    .i()
+    .unwrap();
+    assert_eq!(abs, 2);
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "invocation")]
+
+use std::thread::spawn;
+
+use jni::{errors::Error, objects::JValue};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn call_method_same_name_different_classes() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string("a"));
+    let integer = unwrap(
+        &env,
+        env.new_object("java/lang/Integer", "(I)V", &[JValue::from(5)]),
+    );
+
+    for _ in 0..2 {
+        let hash = unwrap(&env, env.call_method(string, "hashCode", "()I", &[]));
+        assert_eq!(unwrap(&env, hash.i()), 97);
+
+        let hash = unwrap(&env, env.call_method(integer, "hashCode", "()I", &[]));
+        assert_eq!(unwrap(&env, hash.i()), 5);
+    }
+}
+
+#[test]
+pub fn call_method_cached_checks_arg_count() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("abc"));
+
+    let len = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+    assert_eq!(unwrap(&env, len.i()), 3);
+
+    let res = env.call_method(string, "length", "()I", &[JValue::from(1)]);
+    assert!(matches!(res, Err(Error::InvalidArgList(_))));
+
+    let res = env.call_method(string, "noSuchMethod", "()I", &[]);
+    assert!(res.is_err());
+    assert!(unwrap(&env, env.exception_check()));
+    unwrap(&env, env.exception_clear());
+}
+
+#[test]
+pub fn get_and_set_field_cached() {
+    let env = attach_current_thread();
+    let atomic = unwrap(
+        &env,
+        env.new_object(
+            "java/util/concurrent/atomic/AtomicInteger",
+            "(I)V",
+            &[JValue::from(1)],
+        ),
+    );
+
+    for i in 0..3 {
+        unwrap(
+            &env,
+            env.set_field(atomic, "value", "I", JValue::from(i * 10)),
+        );
+        let value = unwrap(&env, env.get_field(atomic, "value", "I"));
+        assert_eq!(unwrap(&env, value.i()), i * 10);
+    }
+
+    let res = env.set_field(atomic, "value", "I", JValue::from(1.0));
+    assert!(matches!(res, Err(Error::WrongJValueType(..))));
+}
+
+#[test]
+pub fn id_cache_shared_between_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            spawn(|| {
+                let env = attach_current_thread();
+                let string = unwrap(&env, env.new_string("hello"));
+                for _ in 0..100 {
+                    let len = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+                    assert_eq!(unwrap(&env, len.i()), 5);
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+pub fn clear_id_cache() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("hello"));
+
+    let len = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+    assert_eq!(unwrap(&env, len.i()), 5);
+
+    env.clear_id_cache();
+
+    let len = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+    assert_eq!(unwrap(&env, len.i()), 5);
+}
+
+#[test]
+pub fn set_field_checks_type_before_looking_up_field() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("abc"));
+
+    // The value is checked against the given type before the field is looked up.
+    let res = env.set_field(string, "noSuchField", "I", JValue::from(1.0));
+    assert!(matches!(res, Err(Error::WrongJValueType(..))));
+    assert!(!unwrap(&env, env.exception_check()));
+
+    let res = env.call_method(string, "noSuchMethod", "()I", &[JValue::from(1)]);
+    assert!(matches!(res, Err(Error::InvalidArgList(_))));
+    assert!(!unwrap(&env, env.exception_check()));
+}
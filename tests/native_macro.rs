@@ -0,0 +1,130 @@
+#![cfg(all(feature = "invocation", feature = "macros"))]
+
+use jni::{
+    errors::Result,
+    java_class,
+    objects::{JClass, JObject, JString},
+    sys::{jint, jobject, jstring},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[jni::native(class = "com/example/Native_Test", native_method, sig = "(II)I")]
+fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+    a + b
+}
+
+#[jni::native(class = "com.example.Native$Inner", name = "greet")]
+fn greet_impl<'a>(env: JNIEnv<'a>, _this: JObject<'a>, name: JString<'a>) -> Result<JString<'a>> {
+    let name: String = env.get_string(name)?.into();
+    env.new_string(format!("Hello, {}!", name))
+}
+
+#[jni::native(
+    class = "com/example/Native",
+    overloaded,
+    sig = "(I[Ljava/lang/String;)Z"
+)]
+fn check(_env: JNIEnv, _class: JClass, value: jint, _names: JObject) -> bool {
+    if value < 0 {
+        panic!("negative value");
+    }
+    value > 0
+}
+
+java_class! {
+    class java.lang.StringBuilder {
+        new(String);
+        fn reverse() -> StringBuilder;
+    }
+}
+
+/// An alias, which the macro cannot tell from its name.
+type Text<'a> = JString<'a>;
+
+#[jni::native(class = "com/example/Native")]
+fn reversed<'a>(env: JNIEnv<'a>, _class: JClass<'a>, text: Text<'a>) -> Result<StringBuilder<'a>> {
+    StringBuilder::new(&env, text)?.reverse(&env)
+}
+
+#[test]
+pub fn native_method_is_exported_with_mangled_name() {
+    let env = attach_current_thread();
+
+    let res = Java_com_example_Native_1Test_add(*env, JClass::from(JObject::null()), 1, 2);
+
+    assert_eq!(res, 3);
+}
+
+#[test]
+pub fn native_method_converts_result() {
+    let env = attach_current_thread();
+    let name = unwrap(&env, env.new_string("world"));
+
+    let res: jstring = Java_com_example_Native_00024Inner_greet(*env, JObject::null(), name);
+    let res: String = unwrap(&env, env.get_string(JString::from(res))).into();
+    assert_eq!(res, "Hello, world!");
+
+    let res = Java_com_example_Native_00024Inner_greet(
+        *env,
+        JObject::null(),
+        JString::from(JObject::null()),
+    );
+    assert!(res.is_null());
+    assert!(unwrap(&env, env.exception_check()));
+    unwrap(&env, env.exception_clear());
+}
+
+#[test]
+pub fn native_method_overloaded_catches_panic() {
+    let env = attach_current_thread();
+
+    let res = Java_com_example_Native_check__I_3Ljava_lang_String_2(
+        *env,
+        JClass::from(JObject::null()),
+        1,
+        JObject::null(),
+    );
+    assert_eq!(res, jni::sys::JNI_TRUE);
+    assert!(!unwrap(&env, env.exception_check()));
+
+    let res = Java_com_example_Native_check__I_3Ljava_lang_String_2(
+        *env,
+        JClass::from(JObject::null()),
+        -1,
+        JObject::null(),
+    );
+    assert_eq!(res, jni::sys::JNI_FALSE);
+    let exception = unwrap(&env, env.take_exception()).expect("exception expected");
+    assert_eq!(exception.class, "java.lang.RuntimeException");
+    assert_eq!(exception.message.as_deref(), Some("negative value"));
+}
+
+#[test]
+pub fn native_method_descriptor() {
+    let method = add_native_method();
+
+    assert_eq!(String::from(method.name), "add");
+    assert_eq!(String::from(method.sig), "(II)I");
+    assert_eq!(
+        method.fn_ptr,
+        Java_com_example_Native_1Test_add as *mut std::os::raw::c_void
+    );
+}
+
+#[test]
+pub fn native_method_returns_java_class_wrapper() {
+    let env = attach_current_thread();
+    let text = unwrap(&env, env.new_string("abc"));
+
+    let res: jobject = Java_com_example_Native_reversed(*env, JClass::from(JObject::null()), text);
+    let res = unwrap(
+        &env,
+        env.call_method(res, "toString", "()Ljava/lang/String;", &[])
+            .and_then(|v| v.l()),
+    );
+    let res: String = unwrap(&env, env.get_string(res.into())).into();
+    assert_eq!(res, "cba");
+}
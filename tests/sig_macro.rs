@@ -0,0 +1,76 @@
+#![cfg(all(feature = "invocation", feature = "macros"))]
+
+use jni::{
+    errors::Error,
+    objects::JValue,
+    sig,
+    signature::{JavaType, Primitive, TypeSignature},
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn sig_builds_parsed_signatures() {
+    let method = sig!((int, java.lang.String[]) -> java.util.Map$Entry);
+    assert_eq!(
+        method.as_str(),
+        "(I[Ljava/lang/String;)Ljava/util/Map$Entry;"
+    );
+    assert_eq!(
+        *method.signature(),
+        TypeSignature::from_str(method.as_str()).unwrap()
+    );
+
+    let field = sig!(double[]);
+    assert_eq!(field.as_str(), "[D");
+    assert_eq!(
+        *field.java_type(),
+        JavaType::Array(Box::new(JavaType::Primitive(Primitive::Double)))
+    );
+}
+
+#[test]
+pub fn call_methods_with_sig() {
+    let env = attach_current_thread();
+
+    let res = unwrap(
+        &env,
+        env.call_static_method(
+            "java/lang/Math",
+            "abs",
+            sig!((int) -> int),
+            &[JValue::from(-10)],
+        ),
+    );
+    assert_eq!(unwrap(&env, res.i()), 10);
+
+    let string = unwrap(&env, env.new_string("hello"));
+    let builder = unwrap(
+        &env,
+        env.new_object(
+            "java/lang/StringBuilder",
+            sig!((java.lang.String)),
+            &[JValue::from(string)],
+        ),
+    );
+    let res = unwrap(
+        &env,
+        env.call_method(builder, "length", sig!(() -> int), &[]),
+    );
+    assert_eq!(unwrap(&env, res.i()), 5);
+
+    let res = env.call_method(builder, "length", sig!(() -> int), &[JValue::from(1)]);
+    assert!(matches!(res, Err(Error::InvalidArgList(_))));
+}
+
+#[test]
+pub fn get_fields_with_sig() {
+    let env = attach_current_thread();
+
+    let res = unwrap(
+        &env,
+        env.get_static_field("java/lang/Integer", "MAX_VALUE", sig!(int)),
+    );
+    assert_eq!(unwrap(&env, res.i()), i32::MAX);
+}
@@ -1,4 +1,10 @@
-use std::sync::{Arc, Once};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Once},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
 
 use jni::{
     errors::Result, objects::JValue, sys::jint, AttachGuard, InitArgsBuilder, JNIEnv, JNIVersion,
@@ -83,3 +89,37 @@ pub fn unwrap<T>(env: &JNIEnv, res: Result<T>) -> T {
         panic!("{:#?}", e);
     })
 }
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs `future` to completion in the current thread.
+#[allow(dead_code)]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Polls `future` once, with a waker that does nothing.
+#[allow(dead_code)]
+pub fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    Pin::new(future).poll(&mut Context::from_waker(&waker))
+}